@@ -1,3 +1,5 @@
+#[allow(unused_imports)]
+// pulls the crate, and so its #[divan::bench] functions, into this binary.
 use wsv::*;
 
 fn main() {
@@ -0,0 +1,53 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "peginator")]
+    {
+        peginator_codegen::Compile::file("src/parsers/wsv.ebnf")
+            .destination(format!(
+                "{}/wsv_peginator.rs",
+                std::env::var("OUT_DIR").unwrap()
+            ))
+            .format()
+            .run_exit_on_error();
+        println!("cargo:rerun-if-changed=src/parsers/wsv.ebnf");
+    }
+
+    #[cfg(feature = "lalrpop")]
+    {
+        lalrpop::Configuration::new()
+            .use_cargo_dir_conventions()
+            .process_file("src/parsers/wsv.lalrpop")
+            .unwrap();
+        println!("cargo:rerun-if-changed=src/parsers/wsv.lalrpop");
+    }
+
+    // Parol's generated modules start with inner attributes, which `include!` cannot handle, so
+    // they are taken out once it is done. The modules in parol.rs allow the same lints.
+    #[cfg(feature = "parol")]
+    {
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        parol::build::Builder::with_explicit_output_dir(&out_dir)
+            .grammar_file("src/parsers/parol/wsv.par")
+            .parser_output_file("parser.rs")
+            .actions_output_file("grammar_trait.rs")
+            .user_type_name("WsvGrammar")
+            .user_trait_module_name("parol::grammar")
+            .minimize_boxed_types()
+            .trim_parse_tree()
+            .disable_recovery()
+            .generate_parser()
+            .unwrap();
+        for file in ["parser.rs", "grammar_trait.rs"] {
+            let path = std::path::Path::new(&out_dir).join(file);
+            let code = std::fs::read_to_string(&path).unwrap();
+            let code = code
+                .lines()
+                .filter(|line| !line.starts_with("#!["))
+                .collect::<Vec<_>>()
+                .join("\n");
+            std::fs::write(&path, code).unwrap();
+        }
+        println!("cargo:rerun-if-changed=src/parsers/parol/wsv.par");
+    }
+}
@@ -3,25 +3,131 @@ use std::error::Error as stdError;
 use std::fs::File;
 
 //use wsv::from_reader;
-// use tracing::subscriber::set_global_default as sgd;
-// use tracing_subscriber::FmtSubscriber as sub;
-// use tracing::Level;
+use wsv::ErrorKind;
 use wsv::Wsv;
-use wsv::first;
+use wsv::{from_reader_with_parser, ColumnUnit, ParseLimits, Parser};
 
 //use wsv::io::from_reader; //this throws an error, since io module the is private.
 use wsv::from_reader; // this does not error, because the path to the item is all pub. Changing the mod publicity, therefore, is more a question of accessibility within the crate, since lib.rs has visibility on every public item.
 fn main() {
-    //sgd(sub::builder().with_max_level(Level::TRACE).finish()).unwrap();
+    #[cfg(feature = "tracing")]
+    subscribe();
     let input = env::args().nth(1).unwrap_or(String::from("welcome"));
-    if let Err(err) = run(input) {
+    if input == "explain" {
+        return explain(env::args().nth(2));
+    }
+    if input == "diagram" {
+        return diagram(env::args().nth(2), env::args().nth(3));
+    }
+    if input == "trace" {
+        if let Err(err) = trace(env::args().nth(2), env::args().nth(3)) {
+            println!("{:?}", err);
+        }
+        return;
+    }
+    if input == "conformance" {
+        if let Err(err) = conformance(env::args().nth(2)) {
+            println!("{:?}", err);
+        }
+        return;
+    }
+    #[cfg(feature = "diagnostics")]
+    if input == "check" {
+        if let Err(err) = check(env::args().nth(2), env::args().nth(3)) {
+            println!("{:?}", err);
+        }
+        return;
+    }
+    if let Err(err) = run(input, env::args().nth(2)) {
         println!("{:?}", err);
     }
 }
 
-fn run(input: String) -> Result<(), Box<dyn stdError>> {
+/// `main 100lines [parser]` pretty-prints an example file, with the fastest parser unless one is named.
+fn run(input: String, parser: Option<String>) -> Result<(), Box<dyn stdError>> {
     let mut file: File = File::open(["./tests/example_files/", &input, ".wsv"].concat())?;
 
-    println!("{}", Wsv(from_reader(&mut file)));
+    let wsv = match parser {
+        Some(name) => from_reader_with_parser(
+            &mut file,
+            name.parse::<Parser>()?,
+            ColumnUnit::default(),
+            ParseLimits::default(),
+        ),
+        None => from_reader(&mut file),
+    };
+    println!("{}", Wsv(wsv));
+    Ok(())
+}
+
+/// `main explain WSV001` prints the long-form explanation of an error code, like `rustc --explain`.
+fn explain(code: Option<String>) {
+    match code.as_deref().and_then(ErrorKind::from_code) {
+        Some(kind) => println!("{}", kind.explain()),
+        None => {
+            println!("Expected one of the following error codes:");
+            for kind in ErrorKind::ALL {
+                println!("{}: {}", kind.code(), kind);
+            }
+        }
+    }
+}
+
+/// `main check path/to/file.wsv [json|sarif]` validates a file and prints the diagnostics.
+#[cfg(feature = "diagnostics")]
+fn check(path: Option<String>, format: Option<String>) -> Result<(), Box<dyn stdError>> {
+    use wsv::diagnostics;
+
+    let path = path.ok_or("Expected a path to check")?;
+    let source = std::fs::read_to_string(&path)?;
+    let found = diagnostics::check(&source, Parser::default(), ColumnUnit::Chars);
+    match format.as_deref() {
+        Some("sarif") => println!("{}", diagnostics::to_sarif(&found, &path)),
+        _ => print!("{}", diagnostics::to_json_lines(&found)),
+    }
+    Ok(())
+}
+
+/// `main conformance [dir]` runs every parser over the conformance suite and prints how each did.
+fn conformance(dir: Option<String>) -> Result<(), Box<dyn stdError>> {
+    let cases = wsv::conformance::load(dir.as_deref().unwrap_or("./tests/conformance"))?;
+    print!("{}", wsv::conformance::run(&cases, Parser::ALL));
     Ok(())
 }
+
+/// `main diagram [mealy|moore] [dot|mermaid]` prints one of the WSV state machines as a graph.
+fn diagram(machine: Option<String>, format: Option<String>) {
+    use wsv::machine::Diagram;
+
+    let diagram = match machine.as_deref() {
+        Some("moore") => Diagram::moore::<wsv::moore::WsvMachine>(),
+        _ => Diagram::mealy::<wsv::mealy::WsvMachine>(),
+    };
+    match format.as_deref() {
+        Some("mermaid") => print!("{}", diagram.to_mermaid()),
+        _ => print!("{}", diagram.to_dot()),
+    }
+}
+
+/// `main trace <parser> <line>` prints what the parser did at each character of the line.
+fn trace(parser: Option<String>, line: Option<String>) -> Result<(), Box<dyn stdError>> {
+    let parser = parser.unwrap_or(String::from("mealy")).parse::<Parser>()?;
+    let line = line.ok_or("Expected a line to trace")?;
+    println!("{}", wsv::trace::explain(&line, parser)?);
+    Ok(())
+}
+
+/// With the `tracing` feature, spans and events go to stderr, at the level named in `RUST_LOG`.
+#[cfg(feature = "tracing")]
+fn subscribe() {
+    use tracing_subscriber::filter::LevelFilter;
+
+    let level = env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::INFO);
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .init();
+}
@@ -0,0 +1,92 @@
+//! Zero-copy archives of parsed documents, using rkyv.
+//!
+//! Parsing a big file every time it is read adds up. With this, a document is parsed once and
+//! written out as an rkyv archive, and from then on the archive can be read straight off disk, or
+//! out of a memory map, with no parsing and no deserialising. The archived document is used where
+//! it lies, and only the bytes which are actually looked at are ever touched.
+//!
+//! Every archive is checked with bytecheck before it is handed out, so a truncated or corrupted
+//! file is an error rather than undefined behaviour. Only documents which parsed without errors
+//! can be archived, since an `Error` can carry any source error at all, so a `Wsv` is archived by
+//! collecting its rows first.
+//!
+//! ```
+//! let document = wsv::first::parse_strict("a - \"b c\"\nd").unwrap();
+//! let bytes = wsv::archive::to_bytes(&document).unwrap();
+//! // write `bytes` to a file, then memory-map it later on.
+//! let archived = wsv::archive::access(&bytes).unwrap();
+//! assert_eq!(archived[0][2].as_str(), Some("b c"));
+//! assert_eq!(archived[0][1], wsv::WsvValue::Null);
+//! ```
+use rkyv::{rancor, util::AlignedVec, vec::ArchivedVec};
+
+use crate::data_model::*;
+
+/// A document as it is laid out in an archive.
+pub type ArchivedDocument = ArchivedVec<ArchivedVec<ArchivedWsvValue>>;
+
+/// Writes a document out as an archive.
+// rkyv archives the outer `Vec` itself, so a slice will not do.
+#[allow(clippy::ptr_arg)]
+pub fn to_bytes(document: &Vec<Vec<WsvValue>>) -> Result<AlignedVec, rancor::Error> {
+    rkyv::to_bytes::<rancor::Error>(document)
+}
+
+/// Checks an archive, and gives back the document inside it without copying anything. The bytes
+/// must be aligned to 16, which they will be if they came from `to_bytes` or a memory map.
+pub fn access(bytes: &[u8]) -> Result<&ArchivedDocument, rancor::Error> {
+    rkyv::access::<ArchivedDocument, rancor::Error>(bytes)
+}
+
+/// Checks an archive, and copies the document out of it.
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Vec<WsvValue>>, rancor::Error> {
+    rkyv::from_bytes::<Vec<Vec<WsvValue>>, rancor::Error>(bytes)
+}
+
+impl ArchivedWsvValue {
+    /// The value, or `None` if it is null.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ArchivedWsvValue::V(value) => Some(value.as_str()),
+            ArchivedWsvValue::Null => None,
+        }
+    }
+}
+
+/// Only this way round, as the other would leave `vec![] == rows` with two possible types.
+impl PartialEq<WsvValue> for ArchivedWsvValue {
+    fn eq(&self, other: &WsvValue) -> bool {
+        match other {
+            WsvValue::V(value) => self.as_str() == Some(value.as_str()),
+            WsvValue::Null => self.as_str().is_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod archive_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let document = vec![
+            vec![WsvValue::from("a"), WsvValue::Null, WsvValue::from("")],
+            vec![],
+            vec![WsvValue::from("b\nc \"d\"")],
+        ];
+        let bytes = to_bytes(&document).unwrap();
+        let archived = access(&bytes).unwrap();
+        assert_eq!(archived.len(), 3);
+        assert_eq!(archived[2][0].as_str(), Some("b\nc \"d\""));
+        assert_eq!(from_bytes(&bytes).unwrap(), document);
+    }
+
+    #[test]
+    fn rejects_a_truncated_archive() {
+        let document = vec![vec![WsvValue::from("a long enough value")]];
+        let bytes = to_bytes(&document).unwrap();
+        let mut truncated = AlignedVec::<16>::new();
+        truncated.extend_from_slice(&bytes[..bytes.len() - 4]);
+        assert!(access(&truncated).is_err());
+    }
+}
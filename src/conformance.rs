@@ -0,0 +1,302 @@
+//! A conformance suite kept as data, so a new edge case needs no Rust at all.
+//!
+//! Each case is a pair of files in one directory. `name.wsv` is the input, read exactly as it is,
+//! so a newline at the end of it is one more, empty, row. `name.expected` sits beside it and has
+//! one line for every row of the input, saying what that row should parse to:
+//!
+//! ```text
+//! "a" - "b c" "say \"hi\"\nand bye"
+//!
+//! error WSV002 3:4
+//! ```
+//!
+//! Values are in double quotes, with `\"`, `\\`, `\n`, `\t` and `\u{3000}` style escapes, and
+//! nulls are `-`. An empty line is an empty row. An error has its code, then its row and column.
+//! A row which is wrong in more than one way still has only the first of its errors, since every
+//! parser reports that one.
+//! Lines starting with `#` are comments, and are not rows.
+//!
+//! `run` checks every case against every parser, through `parse`, and the `Matrix` it gives back
+//! prints as a table of passes and failures. `cargo run --example main conformance` prints the one
+//! for `tests/conformance`.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::{Cell, Color, Table};
+
+use crate::data_model::*;
+use crate::{Parser, WsvParser};
+
+/// One input, and what every row of it should parse to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    /// The file name, without `.wsv`.
+    pub name: String,
+    pub input: String,
+    pub rows: Vec<Expected>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Row(Vec<WsvValue>),
+    Error(Position),
+}
+
+/// Where an error should be reported, and what kind it is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub kind: ErrorKind,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}:{}", self.kind.code(), self.row, self.col)
+    }
+}
+
+/// Reads every case in `dir`, in name order. A `.wsv` file with no sidecar is an error, so a case
+/// cannot be skipped by accident.
+pub fn load(dir: impl AsRef<Path>) -> io::Result<Vec<Case>> {
+    let mut cases = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "wsv") {
+            continue;
+        }
+        let sidecar = path.with_extension("expected");
+        let rows = fs::read_to_string(&sidecar)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.starts_with('#'))
+            .map(|(index, line)| {
+                parse_expected(line).map_err(|reason| {
+                    let at = format!("{}:{}", sidecar.display(), index + 1);
+                    io::Error::new(io::ErrorKind::InvalidData, format!("{at}: {reason}"))
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        cases.push(Case {
+            name: path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into(),
+            input: fs::read_to_string(&path)?,
+            rows,
+        });
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cases)
+}
+
+fn parse_expected(line: &str) -> Result<Expected, String> {
+    match line.strip_prefix("error ") {
+        Some(position) => parse_position(position).map(Expected::Error),
+        None => parse_values(line).map(Expected::Row),
+    }
+}
+
+fn parse_position(position: &str) -> Result<Position, String> {
+    let (code, at) = position
+        .trim()
+        .split_once(' ')
+        .ok_or("expected a code, then row:col")?;
+    let kind = ErrorKind::from_code(code).ok_or(format!("no error code {code}"))?;
+    let (row, col) = at.split_once(':').ok_or("expected row:col")?;
+    let number = |n: &str| n.parse().map_err(|_| format!("{n:?} is not a number"));
+    Ok(Position {
+        kind,
+        row: number(row)?,
+        col: number(col)?,
+    })
+}
+
+fn parse_values(line: &str) -> Result<Vec<WsvValue>, String> {
+    let mut values = vec![];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' => {}
+            '-' => values.push(WsvValue::Null),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next().ok_or("a value with no closing quote")? {
+                        '"' => break,
+                        '\\' => value.push(parse_escape(&mut chars)?),
+                        c => value.push(c),
+                    }
+                }
+                values.push(WsvValue::V(value));
+            }
+            c => return Err(format!("unexpected {c:?}, values must be quoted")),
+        }
+    }
+    Ok(values)
+}
+
+fn parse_escape(chars: &mut std::str::Chars) -> Result<char, String> {
+    match chars.next() {
+        Some('n') => Ok('\n'),
+        Some('t') => Ok('\t'),
+        Some('"') => Ok('"'),
+        Some('\\') => Ok('\\'),
+        Some('u') => {
+            let hex: String = chars.take_while(|&c| c != '}').skip(1).collect();
+            u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(format!("bad unicode escape {hex:?}"))
+        }
+        other => Err(format!("unknown escape {other:?}")),
+    }
+}
+
+impl Case {
+    /// The first row on which `parser` went wrong, along with what it gave instead.
+    pub fn check(&self, parser: Parser) -> Result<(), Failure> {
+        let rows = parser.parse(&self.input);
+        if rows.len() != self.rows.len() {
+            return Err(Failure {
+                row: rows.len().min(self.rows.len()) + 1,
+                got: format!("{} rows instead of {}", rows.len(), self.rows.len()),
+            });
+        }
+        for (index, (got, expected)) in rows.iter().zip(&self.rows).enumerate() {
+            let passed = match (got, expected) {
+                (Ok(values), Expected::Row(expected)) => values == expected,
+                (Err(e), Expected::Error(p)) => (p.kind, p.row, p.col) == (e.kind, e.row, e.col),
+                _ => false,
+            };
+            if !passed {
+                return Err(Failure {
+                    row: index + 1,
+                    got: match got {
+                        Ok(values) => format!("{values:?}"),
+                        Err(e) => format!("{} {}:{}", e.kind.code(), e.row, e.col),
+                    },
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a parser first disagreed with a case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub row: usize,
+    pub got: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: got {}", self.row, self.got)
+    }
+}
+
+/// How every parser did on every case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    pub parsers: Vec<Parser>,
+    pub cases: Vec<String>,
+    /// One row per case, one column per parser.
+    pub results: Vec<Vec<Result<(), Failure>>>,
+}
+
+/// Checks every case against each of `parsers`.
+pub fn run(cases: &[Case], parsers: &[Parser]) -> Matrix {
+    Matrix {
+        parsers: parsers.to_vec(),
+        cases: cases.iter().map(|case| case.name.clone()).collect(),
+        results: cases
+            .iter()
+            .map(|case| parsers.iter().map(|&parser| case.check(parser)).collect())
+            .collect(),
+    }
+}
+
+impl Matrix {
+    /// Every failure, with the case and parser it belongs to.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, Parser, &Failure)> {
+        self.cases
+            .iter()
+            .zip(&self.results)
+            .flat_map(|(case, row)| {
+                self.parsers
+                    .iter()
+                    .zip(row)
+                    .filter_map(move |(&parser, result)| {
+                        Some((case.as_str(), parser, result.as_ref().err()?))
+                    })
+            })
+    }
+
+    /// Whether `parser` passed every case.
+    pub fn passed(&self, parser: Parser) -> bool {
+        self.failures().all(|(_, failed, _)| failed != parser)
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(std::iter::once("").chain(self.parsers.iter().map(Parser::name)));
+        for (case, row) in self.cases.iter().zip(&self.results) {
+            table.add_row(
+                std::iter::once(Cell::new(case)).chain(row.iter().map(|result| match result {
+                    Ok(()) => Cell::new("✓").fg(Color::Green),
+                    Err(_) => Cell::new("✗").fg(Color::DarkRed),
+                })),
+            );
+        }
+        writeln!(f, "{table}")?;
+        for (case, parser, failure) in self.failures() {
+            writeln!(f, "{case}, {parser}: {failure}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod conformance_tests {
+    use super::*;
+
+    #[test]
+    fn sidecar_format() {
+        assert_eq!(
+            parse_expected(r#""a" - "" "b \"c\"\n\u{3000}""#),
+            Ok(Expected::Row(vec![
+                WsvValue::from("a"),
+                WsvValue::Null,
+                WsvValue::from(""),
+                WsvValue::from("b \"c\"\n\u{3000}"),
+            ]))
+        );
+        assert_eq!(parse_expected(""), Ok(Expected::Row(vec![])));
+        assert_eq!(
+            parse_expected("error WSV002 1:10"),
+            Ok(Expected::Error(Position {
+                kind: ErrorKind::MissingWhitespace,
+                row: 1,
+                col: 10
+            }))
+        );
+        assert!(parse_expected("a").is_err());
+        assert!(parse_expected("error WSV999 1:1").is_err());
+    }
+
+    #[test]
+    fn every_parser_conforms() {
+        let cases = load("./tests/conformance").unwrap();
+        assert!(!cases.is_empty());
+        let matrix = run(&cases, Parser::ALL);
+        assert!(matrix.failures().next().is_none(), "{matrix}");
+    }
+}
@@ -3,35 +3,11 @@ use std::fmt::Display;
 
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::{Attribute, Cell, Color, Table};
+use unicode_segmentation::UnicodeSegmentation;
 
+use crate::fixes::Fix;
 use crate::io::calculate_stuff;
 
-#[derive(Default, Debug, Copy, Clone)]
-pub(crate) enum Parser {
-    Nom,
-    State,
-    Mealy,
-    Moore,
-    Split,
-    #[default]
-    First,
-}
-
-type ParserFn = &'static dyn Fn((usize, &str)) -> Result<Vec<WsvValue>, Error>;
-
-impl Parser {
-    pub fn fn_ptr(self) -> ParserFn {
-        match self {
-            Parser::First => &crate::first::parse_line,
-            Parser::Nom => &crate::nom::parse_line,
-            Parser::Split => &crate::split::parse_line,
-            Parser::State => &crate::state::parse_line,
-            Parser::Moore => &crate::moore::parse_line,
-            Parser::Mealy => &crate::mealy::parse_line,
-        }
-    }
-}
-
 #[repr(transparent)]
 pub struct Wsv(pub Vec<Result<Vec<WsvValue>, Error>>);
 
@@ -49,22 +25,10 @@ impl Display for Wsv {
         for line in &self.0 {
             match line {
                 Err(e) => {
-                    table.add_row(vec![Cell::new(e.to_string())
-                        .add_attribute(Attribute::Bold)
-                        .fg(Color::DarkRed)]);
+                    table.add_row(vec![error_cell(e)]);
                 }
                 Ok(line) => {
-                    table.add_row(line.iter().map(|el| {
-                        match el {
-                            WsvValue::Null => Cell::new("NULL")
-                                .add_attribute(Attribute::Bold)
-                                .fg(Color::Green),
-                            WsvValue::V(val) if val.is_empty() => Cell::new("Empty String")
-                                .add_attribute(Attribute::Bold)
-                                .fg(Color::Blue),
-                            WsvValue::V(val) => Cell::new(val),
-                        }
-                    }));
+                    table.add_row(line.iter().map(value_cell));
                 }
             }
         }
@@ -72,7 +36,31 @@ impl Display for Wsv {
     }
 }
 
+/// How a value looks in a table, so that nulls and empty strings stand out from the rest.
+pub(crate) fn value_cell(value: &WsvValue) -> Cell {
+    match value {
+        WsvValue::Null => Cell::new("NULL")
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Green),
+        WsvValue::V(val) if val.is_empty() => Cell::new("Empty String")
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Blue),
+        WsvValue::V(val) => Cell::new(val),
+    }
+}
+
+pub(crate) fn error_cell(e: &Error) -> Cell {
+    Cell::new(e.to_string())
+        .add_attribute(Attribute::Bold)
+        .fg(Color::DarkRed)
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
 pub enum WsvValue {
     V(String),
     Null,
@@ -88,6 +76,9 @@ impl WsvValue {
             WsvValue::V(string) => string.len(),
         }
     }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 impl fmt::Display for WsvValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -117,23 +108,61 @@ impl WsvValue {
         if value == "-" {
             WsvValue::Null
         } else if value.starts_with('"') {
-            WsvValue::V(
-                value[1..value.len() - 1]
-                    .replace("\"/\"", "\n")
-                    .replace("\"\"", "\""),
-            )
+            WsvValue::V(unescape(&value[1..value.len() - 1]))
         } else {
             WsvValue::V(value.to_owned())
         }
     }
 }
 
+/// The inside of a string, with its quotes taken off. Every quote in it should start either `""`
+/// or `"/"`, and escapes are read left to right, so in `""/""` the slash sits between two quotes.
+pub(crate) fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '"' {
+            out.push(c);
+        } else if chars.next() == Some('/') {
+            chars.next();
+            out.push('\n');
+        } else {
+            out.push('"');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod convert_tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(WsvValue::convert("-"), WsvValue::Null);
+        assert_eq!(WsvValue::convert("\"-\""), WsvValue::from("-"));
+        assert_eq!(
+            WsvValue::convert("\"a\"\"b\"/\"c\""),
+            WsvValue::from("a\"b\nc")
+        );
+    }
+
+    /// Replacing `"/"` first would take the middle of `""/""` as a newline.
+    #[test]
+    fn escapes_are_read_left_to_right() {
+        assert_eq!(WsvValue::convert(r#""""/""""#), WsvValue::from("\"/\""));
+        assert_eq!(WsvValue::convert("\"\"\"\"/\"\""), WsvValue::from("\"\n"));
+    }
+}
+
 #[derive(Debug)]
 pub struct Error {
     pub kind: ErrorKind,
     pub row: usize,
     pub col: usize,
     pub source: Option<Box<dyn std::error::Error>>,
+    /// Suggestions to fix this error, filled in by `Error::with_fixes`. Empty if there are none.
+    pub fixes: Vec<Fix>,
 }
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -154,10 +183,57 @@ impl Display for Error {
 impl std::error::Error for Error {}
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "diagnostics", derive(serde::Serialize))]
 pub enum ErrorKind {
     OddDoubleQuotes,
     MissingWhitespace,
+    /// No longer reported, since nom now tells the other two apart. Kept so that WSV003 never
+    /// comes to mean anything else.
     Nom,
+    LimitExceeded,
+    InvalidUtf8,
+}
+
+impl ErrorKind {
+    /// Every kind, in code order.
+    pub const ALL: [ErrorKind; 5] = [
+        ErrorKind::OddDoubleQuotes,
+        ErrorKind::MissingWhitespace,
+        ErrorKind::Nom,
+        ErrorKind::LimitExceeded,
+        ErrorKind::InvalidUtf8,
+    ];
+
+    /// A stable identifier for this kind of error. Codes are never reused or renumbered, so
+    /// they are safe to reference from documentation.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::OddDoubleQuotes => "WSV001",
+            Self::MissingWhitespace => "WSV002",
+            Self::Nom => "WSV003",
+            Self::LimitExceeded => "WSV004",
+            Self::InvalidUtf8 => "WSV005",
+        }
+    }
+
+    /// Looks up a kind by its code, ignoring case, so `wsv001` works too.
+    pub fn from_code(code: &str) -> Option<ErrorKind> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.code().eq_ignore_ascii_case(code))
+    }
+
+    /// The long-form explanation of this error, with an erroneous and a fixed example,
+    /// in the style of `rustc --explain`.
+    pub fn explain(self) -> &'static str {
+        match self {
+            Self::OddDoubleQuotes => include_str!("explanations/WSV001.md"),
+            Self::MissingWhitespace => include_str!("explanations/WSV002.md"),
+            Self::Nom => include_str!("explanations/WSV003.md"),
+            Self::LimitExceeded => include_str!("explanations/WSV004.md"),
+            Self::InvalidUtf8 => include_str!("explanations/WSV005.md"),
+        }
+    }
 }
 
 impl Display for ErrorKind {
@@ -169,12 +245,20 @@ impl Display for ErrorKind {
                 Self::OddDoubleQuotes => "Odd number of double quotes detected",
                 Self::MissingWhitespace => "Whitespace expected",
                 Self::Nom => "Nom Error",
+                Self::LimitExceeded => "Resource limit exceeded",
+                Self::InvalidUtf8 => "Invalid UTF-8",
             }
         )
     }
 }
 
 impl Error {
+    /// Every parser counts `col` in `char`s. This re-expresses it in the given unit, so the
+    /// conversion lives here rather than being repeated in each parser.
+    pub fn in_unit(mut self, line: &str, unit: ColumnUnit) -> Error {
+        self.col = unit.convert(line, self.col);
+        self
+    }
     pub fn new(
         kind: ErrorKind,
         row: usize,
@@ -186,6 +270,159 @@ impl Error {
             row,
             col,
             source,
+            fixes: vec![],
         }
     }
 }
+
+/// The unit in which columns are counted.
+///
+/// The parsers all count 1-indexed `char`s, which is what `Chars` gives you. Editors and LSP clients
+/// tend to want UTF-16 code units, and terminals want graphemes, so a column can be converted
+/// to any of these after the fact with `ColumnUnit::convert`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColumnUnit {
+    #[default]
+    Chars,
+    Bytes,
+    Utf16,
+    Graphemes,
+}
+
+impl ColumnUnit {
+    /// Converts a 1-indexed `char` column on `line` into a 1-indexed column in this unit.
+    ///
+    /// Errors at the end of a line point one past the last character, so any column beyond the
+    /// end of the line is carried over one-for-one.
+    pub fn convert(self, line: &str, col: usize) -> usize {
+        if col == 0 {
+            return 0;
+        }
+        let preceding = col - 1;
+        let (prefix, overflow) = match line.char_indices().nth(preceding) {
+            Some((byte_index, _)) => (&line[..byte_index], 0),
+            None => (line, preceding - line.chars().count()),
+        };
+        self.len(prefix) + overflow + 1
+    }
+
+    /// The length of `s` in this unit.
+    pub fn len(self, s: &str) -> usize {
+        match self {
+            ColumnUnit::Chars => s.chars().count(),
+            ColumnUnit::Bytes => s.len(),
+            ColumnUnit::Utf16 => s.encode_utf16().count(),
+            ColumnUnit::Graphemes => s.graphemes(true).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod column_unit_tests {
+    use super::ColumnUnit::*;
+
+    // "é" is two chars here (e + combining acute), and "😀" is two UTF-16 code units.
+    const LINE: &str = "e\u{301} 😀 x";
+
+    #[test]
+    fn chars_is_identity() {
+        assert_eq!(Chars.convert(LINE, 6), 6);
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(Bytes.convert(LINE, 6), 10);
+    }
+
+    #[test]
+    fn utf16() {
+        assert_eq!(Utf16.convert(LINE, 6), 7);
+    }
+
+    #[test]
+    fn graphemes() {
+        assert_eq!(Graphemes.convert(LINE, 6), 5);
+    }
+
+    #[test]
+    fn past_the_end() {
+        assert_eq!(Bytes.convert(LINE, 8), 12);
+        assert_eq!(Graphemes.convert("", 2), 2);
+    }
+}
+
+#[cfg(test)]
+mod error_code_tests {
+    use super::ErrorKind;
+
+    #[test]
+    fn codes_round_trip() {
+        for kind in ErrorKind::ALL {
+            assert_eq!(ErrorKind::from_code(kind.code()), Some(kind));
+            assert_eq!(
+                ErrorKind::from_code(&kind.code().to_lowercase()),
+                Some(kind)
+            );
+        }
+        assert_eq!(ErrorKind::from_code("WSV999"), None);
+    }
+
+    #[test]
+    fn explanations_have_examples() {
+        for kind in ErrorKind::ALL {
+            assert!(kind.explain().contains("Erroneous example"));
+            assert!(kind.explain().contains("Fixed example"));
+        }
+    }
+
+    /// The lines of the first `wsv` block after "Erroneous example".
+    fn erroneous_example(kind: ErrorKind) -> Vec<&'static str> {
+        let (_, after) = kind.explain().split_once("Erroneous example").unwrap();
+        let (_, block) = after.split_once("```wsv\n").unwrap();
+        let (block, _) = block.split_once("```").unwrap();
+        block.lines().collect()
+    }
+
+    #[test]
+    fn erroneous_examples_give_their_own_code() {
+        use crate::{from_string_with_limits, ParseLimits, Parser, WsvParser};
+
+        for kind in [ErrorKind::OddDoubleQuotes, ErrorKind::MissingWhitespace] {
+            for line in erroneous_example(kind) {
+                for parser in Parser::ALL {
+                    let e = parser.parse_line((0, line)).unwrap_err();
+                    assert_eq!(e.kind, kind, "{parser} on {line:?}");
+                }
+            }
+        }
+
+        // nothing reports WSV003 any more, so its example is an error of some other kind.
+        for line in erroneous_example(ErrorKind::Nom) {
+            for parser in Parser::ALL {
+                let e = parser.parse_line((0, line)).unwrap_err();
+                assert_ne!(e.kind, ErrorKind::Nom, "{parser} on {line:?}");
+            }
+        }
+
+        let limits = ParseLimits {
+            max_cells_per_row: Some(2),
+            ..ParseLimits::default()
+        };
+        let example = erroneous_example(ErrorKind::LimitExceeded).join("\n");
+        let rows = from_string_with_limits(&mut example.as_bytes(), limits);
+        let e = rows.iter().find_map(|row| row.as_ref().err()).unwrap();
+        assert_eq!(e.kind, ErrorKind::LimitExceeded);
+
+        let (_, after) = ErrorKind::InvalidUtf8
+            .explain()
+            .split_once("```hex\n")
+            .unwrap();
+        let bytes = after
+            .split(['\n', ' '])
+            .take_while(|byte| !byte.starts_with('`'))
+            .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+            .collect::<Vec<_>>();
+        let rows = crate::from_reader(&mut bytes.as_slice());
+        assert_eq!(rows[0].as_ref().unwrap_err().kind, ErrorKind::InvalidUtf8);
+    }
+}
@@ -0,0 +1,232 @@
+//! Machine-readable validation output, so WSV data files can be checked in CI like source code.
+//!
+//! A `Diagnostic` is an `Error` flattened into plain data, with its columns counted in whichever
+//! `ColumnUnit` the consumer expects. Diagnostics can be written out as JSON lines, one object
+//! per error, or as a SARIF 2.1.0 log for code-scanning dashboards.
+use serde::Serialize;
+use serde_json::json;
+
+use crate::data_model::*;
+use crate::fixes::unclosed_quote;
+use crate::{Parser, WsvParser};
+
+/// The columns an error covers on its row. `end` is exclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub kind: ErrorKind,
+    pub code: &'static str,
+    pub row: usize,
+    pub col: usize,
+    pub span: Span,
+    pub message: String,
+    #[serde(skip)]
+    pub unit: ColumnUnit,
+    /// The same span in UTF-16 code units, which is what SARIF counts in by default.
+    #[serde(skip)]
+    pub utf16_span: Span,
+}
+
+impl Diagnostic {
+    /// Builds a diagnostic from an error whose `col` is still counted in `char`s, as every parser
+    /// reports it, on the line it was found.
+    pub fn new(error: &Error, line: &str, unit: ColumnUnit) -> Diagnostic {
+        let (start, end) = char_span(error, line);
+        let span_in = |unit: ColumnUnit| Span {
+            start: unit.convert(line, start),
+            end: unit.convert(line, end),
+        };
+        Diagnostic {
+            kind: error.kind,
+            code: error.kind.code(),
+            row: error.row,
+            col: unit.convert(line, error.col),
+            span: span_in(unit),
+            message: error.kind.to_string(),
+            unit,
+            utf16_span: span_in(ColumnUnit::Utf16),
+        }
+    }
+}
+
+/// The `char` columns an error covers, with the end exclusive. The error itself only has a start,
+/// so the rest depends on its kind:
+///
+/// - a missing space covers whatever was written up against the value before it.
+/// - an unclosed string covers the string, from its opening quote to the end of the line.
+/// - anything else covers the rest of the line.
+fn char_span(error: &Error, line: &str) -> (usize, usize) {
+    let len = line.chars().count();
+    let end = match error.kind {
+        ErrorKind::MissingWhitespace => line
+            .chars()
+            .skip(error.col.saturating_sub(1))
+            .position(char::is_whitespace)
+            .map_or(len + 1, |width| error.col + width),
+        _ => len + 1,
+    };
+    let start = match error.kind {
+        ErrorKind::OddDoubleQuotes => unclosed_quote(line).unwrap_or(error.col),
+        _ => error.col,
+    };
+    (start, end.max(start + 1))
+}
+
+/// Validates a whole document with `parser`, returning a diagnostic for every row which fails to
+/// parse.
+pub fn check(source: &str, parser: Parser, unit: ColumnUnit) -> Vec<Diagnostic> {
+    source
+        .split('\n')
+        .enumerate()
+        .filter_map(|(i, line)| {
+            parser
+                .parse_line((i, line))
+                .err()
+                .map(|e| Diagnostic::new(&e, line, unit))
+        })
+        .collect()
+}
+
+/// One JSON object per line, per diagnostic.
+pub fn to_json_lines(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| serde_json::to_string(d).expect("diagnostics always serialise") + "\n")
+        .collect()
+}
+
+/// A SARIF 2.1.0 log with a single run, reporting against the file at `uri`.
+///
+/// SARIF only knows about code point and UTF-16 columns, so every region is written in UTF-16 code
+/// units, whatever unit the diagnostics themselves were made in.
+pub fn to_sarif(diagnostics: &[Diagnostic], uri: &str) -> String {
+    let rules = ErrorKind::ALL
+        .iter()
+        .map(|kind| {
+            json!({
+                "id": kind.code(),
+                "name": format!("{kind:?}"),
+                "shortDescription": { "text": kind.to_string() },
+                "fullDescription": { "text": kind.explain() },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = diagnostics
+        .iter()
+        .map(|d| {
+            json!({
+                "ruleId": d.code,
+                "ruleIndex": ErrorKind::ALL.iter().position(|kind| *kind == d.kind),
+                "level": "error",
+                "message": { "text": d.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": {
+                            "startLine": d.row,
+                            "startColumn": d.utf16_span.start,
+                            "endColumn": d.utf16_span.end,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let run = json!({
+        "tool": {
+            "driver": {
+                "name": "wsv",
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                "rules": rules,
+            },
+        },
+        "artifacts": [{ "location": { "uri": uri } }],
+        "columnKind": "utf16CodeUnits",
+        "results": results,
+    });
+
+    serde_json::to_string_pretty(&json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [run],
+    }))
+    .expect("SARIF always serialises")
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+
+    const INPUT: &str = "fine row\nmmm \"😀\"mmm x\n\"";
+
+    fn check(input: &str, unit: ColumnUnit) -> Vec<Diagnostic> {
+        super::check(input, Parser::First, unit)
+    }
+
+    #[test]
+    fn one_diagnostic_per_bad_row() {
+        let diagnostics = check(INPUT, ColumnUnit::Chars);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.code, d.row, d.col))
+                .collect::<Vec<_>>(),
+            vec![("WSV002", 2, 8), ("WSV001", 3, 2)]
+        );
+    }
+
+    #[test]
+    fn span_is_in_the_requested_unit() {
+        let diagnostics = check(INPUT, ColumnUnit::Utf16);
+        assert_eq!(diagnostics[0].span, Span { start: 9, end: 12 });
+        let diagnostics = check(INPUT, ColumnUnit::Bytes);
+        assert_eq!(diagnostics[0].span, Span { start: 11, end: 14 });
+    }
+
+    #[test]
+    fn spans_cover_the_problem() {
+        let spans = check("a\"b\" c\n\"x\"\"\"/\"y\n-- \"\" \"z #", ColumnUnit::Chars)
+            .iter()
+            .map(|d| (d.span.start, d.span.end))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, [(2, 5), (1, 9), (7, 11)]);
+    }
+
+    #[test]
+    fn json_lines() {
+        let json = to_json_lines(&check(INPUT, ColumnUnit::Chars));
+        assert_eq!(
+            json.lines().next().unwrap(),
+            r#"{"kind":"MissingWhitespace","code":"WSV002","row":2,"col":8,"span":{"start":8,"end":11},"message":"Whitespace expected"}"#
+        );
+        assert_eq!(json.lines().count(), 2);
+    }
+
+    #[test]
+    fn sarif() {
+        // the unit the diagnostics were made in makes no difference to the log.
+        let mut diagnostics = check(INPUT, ColumnUnit::Bytes);
+        diagnostics.extend(check(INPUT, ColumnUnit::Graphemes));
+        let sarif: serde_json::Value =
+            serde_json::from_str(&to_sarif(&diagnostics, "data.wsv")).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["columnKind"], "utf16CodeUnits");
+        assert_eq!(run["results"][1]["ruleId"], "WSV001");
+        for result in [&run["results"][0], &run["results"][2]] {
+            let region = &result["locations"][0]["physicalLocation"]["region"];
+            assert_eq!(
+                (&region["startColumn"], &region["endColumn"]),
+                (&9.into(), &12.into())
+            );
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Runs every parser on the same input, and reports wherever they disagree.
+//!
+//! All of the implementations are meant to give the same answer, so any row where they do not is
+//! a bug in at least one of them. Rows are compared one at a time, through each parser's `parse`,
+//! so one bad row does not hide the rest. Each parser's `parse_strict` is compared too, since it
+//! has to find the first error of the whole document rather than of one row. Errors are compared
+//! by kind, row and column only, since their sources are specific to each parser.
+//!
+//! Some inputs are wrong in more than one way, like `a "b"c"`, which is missing whitespace and has
+//! odd quotes. Every parser reports whichever comes first, so those rows must agree too.
+
+use std::fmt;
+
+use crate::data_model::*;
+use crate::{Parser, WsvParser};
+
+/// What one parser made of one row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Row(Vec<WsvValue>),
+    Error {
+        kind: ErrorKind,
+        row: usize,
+        col: usize,
+    },
+    /// The parser returned fewer rows than some other parser did.
+    Missing,
+    /// Every row, from a `parse_strict` which found no error.
+    Document(Vec<Vec<WsvValue>>),
+}
+
+impl From<Option<&Result<Vec<WsvValue>, Error>>> for Outcome {
+    fn from(result: Option<&Result<Vec<WsvValue>, Error>>) -> Self {
+        match result {
+            Some(Ok(values)) => Outcome::Row(values.clone()),
+            Some(Err(e)) => Outcome::Error {
+                kind: e.kind,
+                row: e.row,
+                col: e.col,
+            },
+            None => Outcome::Missing,
+        }
+    }
+}
+
+impl From<&Result<Vec<Vec<WsvValue>>, Error>> for Outcome {
+    fn from(result: &Result<Vec<Vec<WsvValue>>, Error>) -> Self {
+        match result {
+            Ok(rows) => Outcome::Document(rows.clone()),
+            Err(e) => Outcome::Error {
+                kind: e.kind,
+                row: e.row,
+                col: e.col,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Row(values) => write!(f, "{values:?}"),
+            Outcome::Error { kind, row, col } => write!(f, "{kind} on row {row}, col {col}"),
+            Outcome::Missing => write!(f, "no row at all"),
+            Outcome::Document(rows) => write!(f, "{rows:?}"),
+        }
+    }
+}
+
+/// A row on which the parsers did not all agree, with each distinct outcome and who gave it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Counted from 1, as in `Error`.
+    pub row: usize,
+    pub outcomes: Vec<(Outcome, Vec<Parser>)>,
+}
+
+impl Divergence {
+    /// Whether some parser accepted the row while another rejected it, or left it out.
+    pub fn on_validity(&self) -> bool {
+        let accepted = |(outcome, _): &&(Outcome, Vec<Parser>)| matches!(outcome, Outcome::Row(_));
+        let count = self.outcomes.iter().filter(accepted).count();
+        count != 0 && count != self.outcomes.len()
+    }
+
+    /// Whether two parsers accepted the row, but found different values in it.
+    pub fn on_values(&self) -> bool {
+        self.outcomes
+            .iter()
+            .filter(|(outcome, _)| matches!(outcome, Outcome::Row(_)))
+            .count()
+            > 1
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Row {}:", self.row)?;
+        for (outcome, parsers) in &self.outcomes {
+            let names = parsers.iter().map(Parser::name).collect::<Vec<_>>();
+            writeln!(f, "  {}: {outcome}", names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything `compare` found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub parsers: Vec<Parser>,
+    pub divergences: Vec<Divergence>,
+    /// Each distinct outcome of `parse_strict` and who gave it, if they were not all the same.
+    pub strict: Vec<(Outcome, Vec<Parser>)>,
+}
+
+impl Report {
+    /// Whether every parser gave exactly the same outcome on every row, and on the whole input.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty() && self.strict.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_consistent() {
+            return writeln!(f, "All {} parsers agree.", self.parsers.len());
+        }
+        for divergence in &self.divergences {
+            write!(f, "{divergence}")?;
+        }
+        if !self.strict.is_empty() {
+            writeln!(f, "parse_strict:")?;
+        }
+        for (outcome, parsers) in &self.strict {
+            let names = parsers.iter().map(Parser::name).collect::<Vec<_>>();
+            writeln!(f, "  {}: {outcome}", names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every parser in this build on `input`.
+pub fn compare(input: &str) -> Report {
+    compare_with(input, Parser::ALL)
+}
+
+/// Runs only the given parsers on `input`.
+pub fn compare_with(input: &str, parsers: &[Parser]) -> Report {
+    let results = parsers
+        .iter()
+        .map(|parser| parser.parse(input))
+        .collect::<Vec<_>>();
+    let rows = results.iter().map(Vec::len).max().unwrap_or(0);
+
+    let divergences = (0..rows)
+        .filter_map(|row| {
+            let outcomes = group(
+                parsers
+                    .iter()
+                    .zip(&results)
+                    .map(|(parser, result)| (*parser, Outcome::from(result.get(row)))),
+            );
+            (outcomes.len() > 1).then_some(Divergence {
+                row: row + 1,
+                outcomes,
+            })
+        })
+        .collect();
+
+    let strict = group(
+        parsers
+            .iter()
+            .map(|parser| (*parser, Outcome::from(&parser.parse_strict(input)))),
+    );
+
+    Report {
+        parsers: parsers.to_vec(),
+        divergences,
+        strict: if strict.len() > 1 { strict } else { vec![] },
+    }
+}
+
+/// Each distinct outcome, in the order it was first seen, with every parser which gave it.
+fn group(outcomes: impl Iterator<Item = (Parser, Outcome)>) -> Vec<(Outcome, Vec<Parser>)> {
+    let mut grouped: Vec<(Outcome, Vec<Parser>)> = vec![];
+    for (parser, outcome) in outcomes {
+        match grouped.iter_mut().find(|(seen, _)| *seen == outcome) {
+            Some((_, agreeing)) => agreeing.push(parser),
+            None => grouped.push((outcome, vec![parser])),
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod differential_tests {
+    use super::*;
+    use crate::generate::Shape;
+    use proptest::prelude::*;
+
+    /// Each of these rows is wrong in two ways, or can be read in two ways. `""/` is an empty
+    /// string with a value straight after it, not the start of a `"/"` which is never finished.
+    #[test]
+    fn agree_on_the_first_error() {
+        for input in [
+            "a\"",
+            "\"\"a\"",
+            "\"\"/",
+            "\"\"/a\"",
+            "\"\"/ b\n\"\"\"\"/",
+            "\"a\"b",
+        ] {
+            let report = compare(input);
+            assert!(report.is_consistent(), "{input:?}\n{report}");
+        }
+    }
+
+    /// A string left open on one row comes before a missing whitespace on the next, and the
+    /// state machines used to count the columns of every row from the start of the document.
+    #[test]
+    fn agree_on_the_first_error_of_a_document() {
+        for input in ["\"x\nb\"c\"", "a \"b\nc\"d\"", "a\n\"x\nb\"c\""] {
+            let report = compare(input);
+            assert!(report.is_consistent(), "{input:?}\n{report}");
+        }
+    }
+
+    /// Split used to lose count of the columns after an escape, and to report the second of two
+    /// missing whitespaces around a value.
+    #[test]
+    fn agree_after_escapes() {
+        let report = compare("\"\"\"\"a\n\"-\"a\"\"\t");
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    #[test]
+    fn agree_on_the_welcome_file() {
+        let input = std::fs::read_to_string("./tests/example_files/welcome.wsv").unwrap();
+        let report = compare(&input);
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    #[test]
+    fn reports_a_divergence() {
+        let report = compare_with("a b\nc", &[Parser::First, Parser::State]);
+        assert!(report.is_consistent());
+
+        let divergence = Divergence {
+            row: 1,
+            outcomes: vec![
+                (Outcome::Row(vec![]), vec![Parser::First]),
+                (Outcome::Missing, vec![Parser::State]),
+            ],
+        };
+        assert!(divergence.on_validity());
+        assert!(!divergence.on_values());
+    }
+
+    proptest! {
+        #[test]
+        fn agree_on_valid_documents(document in Shape::default().strategy()) {
+            let report = compare(&document.text);
+            prop_assert!(report.is_consistent(), "{:?}\n{}", document.text, report);
+        }
+
+        #[test]
+        fn agree_on_every_row(input in "[ab \t\"#/\n-]{0,20}") {
+            let report = compare(&input);
+            prop_assert!(report.is_consistent(), "{:?}\n{}", input, report);
+        }
+    }
+}
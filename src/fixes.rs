@@ -0,0 +1,195 @@
+//! Machine-applicable suggestions for the two mistakes that make up most broken WSV files: a
+//! missing space and an unclosed quote.
+//!
+//! Suggestions are worked out from the error and the line alone, so they are the same whichever
+//! parser found the error. Every candidate is checked by re-parsing the fixed line, and only
+//! kept if it gets past the original error.
+use crate::data_model::*;
+
+/// A single insertion which fixes an `Error`.
+///
+/// `col` is always counted in `char`s, whatever `ColumnUnit` the error is reported in, since it
+/// is for rewriting the text rather than for display.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fix {
+    pub message: &'static str,
+    pub row: usize,
+    pub col: usize,
+    pub insert: &'static str,
+}
+
+impl Error {
+    /// Fills in `fixes` with every suggestion which resolves this error on the given line.
+    pub fn with_fixes(mut self, line: &str) -> Error {
+        self.fixes = suggest_fixes(self.kind, self.row, self.col, line);
+        self
+    }
+}
+
+fn suggest_fixes(kind: ErrorKind, row: usize, col: usize, line: &str) -> Vec<Fix> {
+    let candidates = match kind {
+        ErrorKind::MissingWhitespace => vec![Fix {
+            message: "insert whitespace",
+            row,
+            col,
+            insert: " ",
+        }],
+        ErrorKind::OddDoubleQuotes => {
+            let mut candidates = vec![Fix {
+                message: "close the string",
+                row,
+                col,
+                insert: "\"",
+            }];
+            if let Some(open) = unclosed_quote(line) {
+                candidates.push(Fix {
+                    message: "double the quote",
+                    row,
+                    col: open + 1,
+                    insert: "\"",
+                });
+            }
+            candidates
+        }
+        ErrorKind::Nom | ErrorKind::LimitExceeded | ErrorKind::InvalidUtf8 => vec![],
+    };
+
+    let mut fixes: Vec<Fix> = vec![];
+    for fix in candidates {
+        let duplicate = fixes
+            .iter()
+            .any(|f| f.col == fix.col && f.insert == fix.insert);
+        if !duplicate && resolves(&fix, col, line) {
+            fixes.push(fix);
+        }
+    }
+    fixes
+}
+
+/// A fix resolves an error if the fixed line either parses, or fails somewhere further along.
+fn resolves(fix: &Fix, col: usize, line: &str) -> bool {
+    let fixed = insert_at(line, fix.col, fix.insert);
+    match crate::first::parse_line((fix.row - 1, &fixed)) {
+        Ok(_) => true,
+        Err(e) => e.col > col + fix.insert.chars().count(),
+    }
+}
+
+/// The column of the quote which opens the string left unclosed at the end of the line, if any.
+pub(crate) fn unclosed_quote(line: &str) -> Option<usize> {
+    let mut chars = line.chars().enumerate().peekable();
+    let mut open = None;
+    while let Some((i, c)) = chars.next() {
+        match (open, c) {
+            (None, '#') => break,
+            (None, '"') => open = Some(i + 1),
+            (Some(_), '"') => match chars.peek() {
+                Some((_, '"')) => {
+                    chars.next();
+                }
+                Some((_, '/')) => {
+                    chars.next();
+                    chars.next();
+                }
+                _ => open = None,
+            },
+            _ => {}
+        }
+    }
+    open
+}
+
+fn insert_at(line: &str, col: usize, insert: &str) -> String {
+    let byte_index = line
+        .char_indices()
+        .nth(col.saturating_sub(1))
+        .map_or(line.len(), |(i, _)| i);
+    [&line[..byte_index], insert, &line[byte_index..]].concat()
+}
+
+/// Rewrites `source` with the given fixes applied.
+///
+/// Fixes from the same error are alternatives, so pass at most one per error. Columns refer to
+/// the original text; insertions are applied right to left so they do not disturb each other.
+pub fn apply_fixes(source: &str, fixes: &[Fix]) -> String {
+    let mut fixes = fixes.iter().collect::<Vec<_>>();
+    fixes.sort_by_key(|fix| std::cmp::Reverse(fix.col));
+    source
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            fixes
+                .iter()
+                .filter(|fix| fix.row == i + 1)
+                .fold(line.to_owned(), |line, fix| {
+                    insert_at(&line, fix.col, fix.insert)
+                })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod fix_tests {
+    use super::*;
+    use crate::first::parse_line;
+
+    fn fixes_for(line: &str) -> Vec<Fix> {
+        parse_line((0, line)).unwrap_err().with_fixes(line).fixes
+    }
+
+    fn fixed(line: &str) -> String {
+        apply_fixes(line, &fixes_for(line)[..1])
+    }
+
+    #[test]
+    fn missing_whitespace_inserts_a_space() {
+        assert_eq!(
+            fixes_for(r##"mmm "mmm"mmm"##),
+            vec![Fix {
+                message: "insert whitespace",
+                row: 1,
+                col: 10,
+                insert: " "
+            }]
+        );
+        assert_eq!(fixed(r##"mmm "mmm"mmm"##), r##"mmm "mmm" mmm"##);
+        assert_eq!(fixed(r##"mmm"mmm" mmm"##), r##"mmm "mmm" mmm"##);
+    }
+
+    #[test]
+    fn one_quote_is_closed() {
+        let fixes = fixes_for(r##"""##);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(apply_fixes(r##"""##, &fixes), r##""""##);
+    }
+
+    #[test]
+    fn odd_quotes_can_be_closed_or_doubled() {
+        const INPUT: &str = r##"somthing " somethingelse"##;
+        let fixes = fixes_for(INPUT);
+        assert_eq!(
+            fixes.iter().map(|f| (f.message, f.col)).collect::<Vec<_>>(),
+            vec![("close the string", 25), ("double the quote", 11)]
+        );
+        assert_eq!(
+            apply_fixes(INPUT, &fixes[..1]),
+            r##"somthing " somethingelse""##
+        );
+        assert_eq!(
+            apply_fixes(INPUT, &fixes[1..]),
+            r##"somthing "" somethingelse"##
+        );
+    }
+
+    #[test]
+    fn fixes_only_touch_their_row() {
+        let fix = Fix {
+            message: "insert whitespace",
+            row: 2,
+            col: 2,
+            insert: " ",
+        };
+        assert_eq!(apply_fixes("a\nb\"c\"", &[fix]), "a\nb \"c\"");
+    }
+}
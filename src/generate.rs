@@ -0,0 +1,301 @@
+//! Made-up WSV documents of any shape, for benchmarks and tests.
+//!
+//! A document is built from a `Shape` and a seed, and the same pair always gives the same
+//! document, on any machine and with any version of any dependency. That is why the random numbers
+//! come from a few lines of SplitMix64 below rather than from a crate. So a big corpus never has to
+//! be checked in, it can just be built again whenever it is needed.
+//!
+//! Every document comes with the answer: what each row should parse to, or which error was
+//! planted on it. Each error is the last thing on its row, so it is the first one any parser finds.
+//!
+//! ```
+//! use wsv::generate::Shape;
+//!
+//! let shape = Shape { rows: 3, errors: 0.5, ..Shape::default() };
+//! let document = shape.document(42);
+//! assert_eq!(document.text, shape.document(42).text);
+//! assert_eq!(document.rows.len(), 3);
+//! ```
+//!
+//! With the `proptest` feature, `Shape::strategy` turns a shape into a proptest `Strategy`.
+use std::ops::RangeInclusive;
+
+use crate::data_model::*;
+
+/// What sort of document to make. Every ratio is a probability, from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shape {
+    pub rows: usize,
+    /// How many cells each row has, picked evenly from this range.
+    pub columns: RangeInclusive<usize>,
+    /// How many characters each value has, before any quoting or escaping.
+    pub value_length: RangeInclusive<usize>,
+    /// How often a value is written as a string, with double quotes around it.
+    pub quoting: f64,
+    /// How often each character in a string is a `""` or a `"/"` instead.
+    pub escapes: f64,
+    /// How often a piece of whitespace is something other than spaces and tabs, like U+3000.
+    pub unicode_whitespace: f64,
+    /// How often a row ends with a comment.
+    pub comments: f64,
+    /// How often a cell is a null.
+    pub nulls: f64,
+    /// How often a row ends with a missing space or an unclosed string.
+    pub errors: f64,
+}
+
+impl Default for Shape {
+    /// A bit of everything, and no errors.
+    fn default() -> Self {
+        Shape {
+            rows: 10,
+            columns: 0..=6,
+            value_length: 0..=8,
+            quoting: 0.3,
+            escapes: 0.1,
+            unicode_whitespace: 0.1,
+            comments: 0.2,
+            nulls: 0.1,
+            errors: 0.0,
+        }
+    }
+}
+
+/// A generated document, and what it should parse to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    pub text: String,
+    /// One per row of `text`, with the kind of error planted there, if any.
+    pub rows: Vec<Result<Vec<WsvValue>, ErrorKind>>,
+}
+
+impl Shape {
+    /// Builds the document for this seed.
+    pub fn document(&self, seed: u64) -> Document {
+        let mut rng = SplitMix64(seed);
+        let mut lines = Vec::with_capacity(self.rows);
+        let rows = (0..self.rows)
+            .map(|_| {
+                let mut line = String::new();
+                let row = self.row(&mut rng, &mut line);
+                lines.push(line);
+                row
+            })
+            .collect();
+        Document {
+            text: lines.join("\n"),
+            rows,
+        }
+    }
+
+    fn row(&self, rng: &mut SplitMix64, line: &mut String) -> Result<Vec<WsvValue>, ErrorKind> {
+        let mut values = vec![];
+        if rng.chance(0.3) {
+            self.whitespace(rng, line);
+        }
+        for i in 0..rng.between(&self.columns) {
+            if i > 0 {
+                self.whitespace(rng, line);
+            }
+            values.push(self.cell(rng, line));
+        }
+
+        if rng.chance(self.errors) {
+            if !values.is_empty() {
+                self.whitespace(rng, line);
+            }
+            return Err(self.error(rng, line));
+        }
+
+        if rng.chance(0.2) {
+            self.whitespace(rng, line);
+        }
+        if rng.chance(self.comments) {
+            // a comment can follow a cell directly, and can have anything at all in it.
+            if rng.chance(0.5) {
+                self.whitespace(rng, line);
+            }
+            line.push('#');
+            for _ in 0..rng.between(&self.value_length) {
+                line.push(rng.pick(STRING_CHARS));
+            }
+        }
+        Ok(values)
+    }
+
+    fn cell(&self, rng: &mut SplitMix64, line: &mut String) -> WsvValue {
+        if rng.chance(self.nulls) {
+            line.push('-');
+            return WsvValue::Null;
+        }
+        if rng.chance(self.quoting) {
+            return WsvValue::V(self.string(rng, line));
+        }
+        // a value needs at least one character, and a lone dash would be a null.
+        let mut value = String::new();
+        for _ in 0..rng.between(&self.value_length).max(1) {
+            value.push(rng.pick(VALUE_CHARS));
+        }
+        if value == "-" {
+            value.push('-');
+        }
+        line.push_str(&value);
+        WsvValue::V(value)
+    }
+
+    /// Writes a closed string, and gives back what it holds.
+    fn string(&self, rng: &mut SplitMix64, line: &mut String) -> String {
+        let mut value = String::new();
+        line.push('"');
+        for _ in 0..rng.between(&self.value_length) {
+            let c = if rng.chance(self.escapes) {
+                if rng.chance(0.5) {
+                    line.push_str("\"\"");
+                    '"'
+                } else {
+                    line.push_str("\"/\"");
+                    '\n'
+                }
+            } else {
+                let c = rng.pick(STRING_CHARS);
+                line.push(c);
+                c
+            };
+            value.push(c);
+        }
+        line.push('"');
+        value
+    }
+
+    fn error(&self, rng: &mut SplitMix64, line: &mut String) -> ErrorKind {
+        if rng.chance(0.5) {
+            // a string with no end, and so nothing after it.
+            line.push('"');
+            for _ in 0..rng.between(&self.value_length) {
+                line.push(rng.pick(VALUE_CHARS));
+            }
+            ErrorKind::OddDoubleQuotes
+        } else {
+            line.push(rng.pick(VALUE_CHARS));
+            self.string(rng, line);
+            ErrorKind::MissingWhitespace
+        }
+    }
+
+    fn whitespace(&self, rng: &mut SplitMix64, line: &mut String) {
+        for _ in 0..rng.between(&(1..=2)) {
+            if rng.chance(self.unicode_whitespace) {
+                line.push(rng.pick(UNICODE_WHITESPACE));
+            } else {
+                line.push(rng.pick(&[' ', ' ', ' ', '\t']));
+            }
+        }
+    }
+}
+
+/// Anything but whitespace, double quotes and `#`, with a few characters from outside ASCII.
+const VALUE_CHARS: &[char] = &[
+    'a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '1', '9', '-', '/', '.', ',', '\'', '\\', 'é',
+    'ß', '日', '😀',
+];
+
+/// Strings can also hold whitespace, `#` and anything a value can.
+const STRING_CHARS: &[char] = &[
+    'a', 'b', 'z', 'Z', '0', '9', '-', '/', '#', ' ', '\t', '\'', 'é', '日', '😀', '\u{3000}',
+];
+
+/// Whitespace other than spaces and tabs, all of which `char::is_whitespace` accepts. Carriage
+/// returns are left out, since a reader takes one before a newline as part of the line ending.
+const UNICODE_WHITESPACE: &[char] = &[
+    '\u{0B}', '\u{0C}', '\u{85}', '\u{A0}', '\u{1680}', '\u{2000}', '\u{2003}', '\u{200A}',
+    '\u{2028}', '\u{2029}', '\u{202F}', '\u{205F}', '\u{3000}',
+];
+
+/// The small, fast generator from Java's `SplittableRandom`. Good enough for test data, and it
+/// can never change underneath us.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        // the top 53 bits, as a float in [0, 1).
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    fn between(&mut self, range: &RangeInclusive<usize>) -> usize {
+        let span = range.end().saturating_sub(*range.start()) as u64 + 1;
+        range.start() + (self.next() % span) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.between(&(0..=items.len() - 1))]
+    }
+}
+
+#[cfg(any(test, feature = "proptest"))]
+impl Shape {
+    /// Documents of this shape, one per seed. Shrinking a seed does not make a document any
+    /// smaller, so a failing case is best reported with its text.
+    pub fn strategy(self) -> impl proptest::strategy::Strategy<Value = Document> {
+        use proptest::prelude::*;
+        any::<u64>().prop_map(move |seed| self.document(seed))
+    }
+}
+
+#[cfg(test)]
+mod generate_tests {
+    use super::*;
+    use crate::{Parser, WsvParser};
+    use proptest::prelude::*;
+
+    #[test]
+    fn the_same_seed_gives_the_same_document() {
+        let shape = Shape {
+            rows: 50,
+            errors: 0.2,
+            ..Shape::default()
+        };
+        assert_eq!(shape.document(7), shape.document(7));
+        assert_ne!(shape.document(7).text, shape.document(8).text);
+        assert_eq!(shape.document(7).text.split('\n').count(), 50);
+    }
+
+    #[test]
+    fn errors_only_where_asked() {
+        let valid = Shape {
+            rows: 100,
+            ..Shape::default()
+        };
+        assert!(valid.document(1).rows.iter().all(Result::is_ok));
+
+        let invalid = Shape {
+            errors: 1.0,
+            ..valid
+        };
+        assert!(invalid.document(1).rows.iter().all(Result::is_err));
+    }
+
+    proptest! {
+        #[test]
+        fn every_parser_gets_the_answer(document in Shape { errors: 0.2, ..Shape::default() }.strategy()) {
+            for parser in Parser::ALL {
+                let rows = parser.parse(&document.text);
+                prop_assert_eq!(rows.len(), document.rows.len(), "{} on {:?}", parser, document.text);
+                for (row, expected) in rows.iter().zip(&document.rows) {
+                    match (row, expected) {
+                        (Ok(values), Ok(expected)) => prop_assert_eq!(values, expected, "{} on {:?}", parser, document.text),
+                        (Err(e), Err(kind)) => prop_assert_eq!(e.kind, *kind, "{} on {:?}: {}", parser, document.text, e),
+                        _ => prop_assert!(false, "{} on {:?}: {:?} instead of {:?}", parser, document.text, row, expected),
+                    }
+                }
+            }
+        }
+    }
+}
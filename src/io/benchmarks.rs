@@ -1,48 +1,150 @@
-const PARSERS: [Parser; 6] = [
-    Parser::Nom,
-    Parser::State,
-    Parser::Mealy,
-    Parser::Moore,
-    Parser::Split,
-    Parser::First,
-];
-
 use std::fs::File;
+use std::sync::LazyLock;
 
 use super::*;
-use crate::data_model::Parser;
+use crate::generate::Shape;
 use divan::black_box;
 
-#[divan::bench(args = PARSERS, sample_count = 1000)]
+#[divan::bench(args = Parser::ALL, sample_count = 1000)]
 fn reader_one_big_line(parser: Parser) {
     from_reader_with_parser(
         black_box(&mut File::open("./tests/example_files/1bigline.wsv").unwrap()),
         parser,
+        ColumnUnit::Chars,
+        ParseLimits::default(),
     );
 }
-#[divan::bench(args = PARSERS, sample_count = 10)]
-fn reader_lines_100_000(parser: Parser) {
-    from_reader_with_parser(
-        black_box(&mut File::open("./tests/example_files/100000lines.wsv").unwrap()),
-        parser,
-    );
+#[divan::bench(args = Parser::ALL, sample_count = 10)]
+fn reader_lines_100_000(bencher: divan::Bencher, parser: Parser) {
+    let input = &*LINES_100_000;
+    bencher.bench(|| {
+        from_reader_with_parser(
+            black_box(&mut input.as_bytes()),
+            parser,
+            ColumnUnit::Chars,
+            ParseLimits::default(),
+        )
+    });
 }
-#[divan::bench(args = PARSERS, sample_count = 10)]
-fn reader_comments_100_000(parser: Parser) {
-    from_reader_with_parser(
-        black_box(&mut File::open("./tests/example_files/100000comments.wsv").unwrap()),
-        parser,
-    );
+#[divan::bench(args = Parser::ALL, sample_count = 10)]
+fn reader_comments_100_000(bencher: divan::Bencher, parser: Parser) {
+    let input = &*COMMENTS_100_000;
+    bencher.bench(|| {
+        from_reader_with_parser(
+            black_box(&mut input.as_bytes()),
+            parser,
+            ColumnUnit::Chars,
+            ParseLimits::default(),
+        )
+    });
+}
+
+// These are too big to check in, and take a moment to build, so they are built once, on demand.
+static LINES_100_000: LazyLock<String> = LazyLock::new(|| {
+    Shape {
+        rows: 100_000,
+        ..Shape::default()
+    }
+    .document(0)
+    .text
+});
+static COMMENTS_100_000: LazyLock<String> = LazyLock::new(|| {
+    Shape {
+        rows: 100_000,
+        columns: 0..=1,
+        value_length: 0..=40,
+        comments: 1.0,
+        ..Shape::default()
+    }
+    .document(0)
+    .text
+});
+
+/// Pest's grammar is written for whole documents, so the two PEG grammars are compared on one.
+#[cfg(all(feature = "peg", feature = "pest"))]
+mod peg_vs_pest {
+    use divan::black_box;
+    use std::fs::read_to_string;
+
+    fn input() -> String {
+        read_to_string("./tests/example_files/100lines.wsv").unwrap()
+    }
+
+    #[divan::bench(sample_count = 1000)]
+    fn peg_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::peg::parse_strict(black_box(&input)));
+    }
+
+    #[divan::bench(sample_count = 1000)]
+    fn pest_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::pest::parse_strict(black_box(&input)));
+    }
+}
+
+/// The same again, for the LR(1) grammar against the PEG one.
+#[cfg(all(feature = "lalrpop", feature = "pest"))]
+mod lalrpop_vs_pest {
+    use divan::black_box;
+    use std::fs::read_to_string;
+
+    fn input() -> String {
+        read_to_string("./tests/example_files/100lines.wsv").unwrap()
+    }
+
+    #[divan::bench(sample_count = 1000)]
+    fn lalrpop_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::lalrpop::parse_strict(black_box(&input)));
+    }
+
+    #[divan::bench(sample_count = 1000)]
+    fn pest_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::pest::parse_strict(black_box(&input)));
+    }
+}
+
+/// Chumsky is built for lossy parsing, so it is compared on a whole document with a bad line in
+/// it, against the other general purpose parsing libraries.
+#[cfg(all(feature = "chumsky", feature = "nom"))]
+mod chumsky_vs_nom_vs_pest {
+    use divan::black_box;
+    use std::fs::read_to_string;
+
+    fn input() -> String {
+        read_to_string("./tests/example_files/100lines.wsv").unwrap() + "\nmmm\"AAA\"mmm"
+    }
+
+    #[divan::bench(sample_count = 1000)]
+    fn chumsky_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::chumsky::parse(black_box(&input)));
+    }
+
+    #[divan::bench(sample_count = 1000)]
+    fn nom_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::nom::parse(black_box(&input)));
+    }
+
+    #[cfg(feature = "pest")]
+    #[divan::bench(sample_count = 1000)]
+    fn pest_lines_100(bencher: divan::Bencher) {
+        let input = input();
+        bencher.bench(|| crate::pest::parse_strict(black_box(&input)));
+    }
 }
 
-// #[divan::bench(args = PARSERS, sample_count = 1000)]
+// #[divan::bench(args = Parser::ALL, sample_count = 1000)]
 // fn string_one_big_line(parser: Parser) {
 //     from_string_with_parser(
 //         black_box(&mut File::open("./tests/example_files/1bigline.wsv").unwrap()),
 //         parser,
 //     );
 // }
-// #[divan::bench(args = PARSERS, sample_count = 10)]
+// #[divan::bench(args = Parser::ALL, sample_count = 10)]
 // fn string_lines_100_000(parser: Parser) {
 //     from_string_with_parser(
 //         black_box(&mut File::open("./tests/example_files/100000lines.wsv").unwrap()),
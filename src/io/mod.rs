@@ -2,33 +2,174 @@ use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Read;
 
-use crate::data_model::Parser;
+use crate::data_model::ErrorKind;
+use crate::limits::{chars_in, push_until_limit};
+use crate::telemetry;
+use crate::ColumnUnit;
 use crate::Error;
+use crate::ParseLimits;
+use crate::Parser;
+use crate::WsvParser;
 use crate::WsvValue;
 
+/// Every row, one line at a time, with the fastest parser. A row which is not valid UTF-8 is an
+/// `ErrorKind::InvalidUtf8`, and the rows after it are still read. No limits are checked, so for
+/// untrusted input use `from_reader_with_limits`.
 pub fn from_reader(i: &mut impl Read) -> Vec<Result<Vec<WsvValue>, Error>> {
-    from_reader_with_parser(i, Parser::default())
+    from_reader_with_parser(
+        i,
+        Parser::default(),
+        ColumnUnit::default(),
+        ParseLimits::default(),
+    )
+}
+
+/// As `from_reader`, but with error columns counted in the given unit.
+pub fn from_reader_in(i: &mut impl Read, unit: ColumnUnit) -> Vec<Result<Vec<WsvValue>, Error>> {
+    from_reader_with_parser(i, Parser::default(), unit, ParseLimits::default())
 }
 
-fn from_reader_with_parser(i: &mut impl Read, parser: Parser) -> Vec<Result<Vec<WsvValue>, Error>> {
-    BufReader::new(i)
-        .lines()
-        .enumerate()
-        .map(|(i, l)| (parser.fn_ptr())((i, &l.unwrap())))
-        .collect()
+/// As `from_reader`, but stops with an `ErrorKind::LimitExceeded` at the first breached limit.
+/// Lines are read no further than the limits allow, and rows are checked before they are parsed.
+/// For untrusted input, set `max_line_length` too, since it is the only limit on how much of one
+/// line is held in memory.
+pub fn from_reader_with_limits(
+    i: &mut impl Read,
+    limits: ParseLimits,
+) -> Vec<Result<Vec<WsvValue>, Error>> {
+    from_reader_with_parser(i, Parser::default(), ColumnUnit::default(), limits)
+}
+
+/// As `from_reader`, but with every option spelled out, including which implementation to use.
+pub fn from_reader_with_parser(
+    i: &mut impl Read,
+    parser: Parser,
+    unit: ColumnUnit,
+    limits: ParseLimits,
+) -> Vec<Result<Vec<WsvValue>, Error>> {
+    let timer = telemetry::document(parser);
+    let mut reader = BufReader::new(i);
+    let mut out = vec![];
+    let mut total = 0;
+    let mut buf = vec![];
+    loop {
+        buf.clear();
+        let read = (&mut reader)
+            .take(limits.line_budget(total))
+            .read_until(b'\n', &mut buf)
+            .unwrap();
+        if read == 0 {
+            break;
+        }
+        let row = out.len() + 1;
+        let within_total = limits.check_total(row, total, &buf);
+        total += read;
+        // The same line endings as `BufRead::lines`.
+        if buf.ends_with(b"\n") {
+            buf.pop();
+            if buf.ends_with(b"\r") {
+                buf.pop();
+            }
+        }
+        let result = match within_total.and_then(|_| limits.check_line_bytes(row, &buf)) {
+            Ok(_) => parse_line_in(parser, unit, limits, (row - 1, &buf)),
+            Err(e) => rejected(e.in_unit(&String::from_utf8_lossy(&buf), unit)),
+        };
+        if push_until_limit(&mut out, result) {
+            break;
+        }
+    }
+    timer.document(&out);
+    out
 }
 
+/// As `from_reader`, but the whole input is read into memory first. No limits are checked here
+/// either, so for untrusted input use `from_string_with_limits`.
 pub fn from_string(i: &mut impl Read) -> Vec<Result<Vec<WsvValue>, Error>> {
-    from_string_with_parser(i, Parser::default())
+    from_string_with_parser(
+        i,
+        Parser::default(),
+        ColumnUnit::default(),
+        ParseLimits::default(),
+    )
+}
+
+/// As `from_string`, but with error columns counted in the given unit.
+pub fn from_string_in(i: &mut impl Read, unit: ColumnUnit) -> Vec<Result<Vec<WsvValue>, Error>> {
+    from_string_with_parser(i, Parser::default(), unit, ParseLimits::default())
+}
+
+/// As `from_string`, but stops with an `ErrorKind::LimitExceeded` at the first breached limit.
+/// No more than `max_total_bytes` are read into memory.
+pub fn from_string_with_limits(
+    i: &mut impl Read,
+    limits: ParseLimits,
+) -> Vec<Result<Vec<WsvValue>, Error>> {
+    from_string_with_parser(i, Parser::default(), ColumnUnit::default(), limits)
+}
+
+/// As `from_string`, but with every option spelled out, including which implementation to use.
+pub fn from_string_with_parser(
+    i: &mut impl Read,
+    parser: Parser,
+    unit: ColumnUnit,
+    limits: ParseLimits,
+) -> Vec<Result<Vec<WsvValue>, Error>> {
+    let timer = telemetry::document(parser);
+    let mut buf = vec![];
+    let budget = limits
+        .max_total_bytes
+        .map_or(u64::MAX, |max| max as u64 + 1);
+    i.take(budget).read_to_end(&mut buf).unwrap();
+    let mut out = vec![];
+    let mut total = 0;
+    for (i, line) in buf.split(|byte| *byte == b'\n').enumerate() {
+        let with_newline = &buf[total..(total + line.len() + 1).min(buf.len())];
+        let result = match limits.check_total(i + 1, total, with_newline) {
+            Ok(_) => parse_line_in(parser, unit, limits, (i, line)),
+            Err(e) => rejected(e.in_unit(&String::from_utf8_lossy(line), unit)),
+        };
+        total += with_newline.len();
+        if push_until_limit(&mut out, result) {
+            break;
+        }
+    }
+    timer.document(&out);
+    out
+}
+
+/// The one place where lines are decoded, limits are checked, fixes are suggested and column units
+/// are applied. The parsers themselves always count `char`s.
+fn parse_line_in(
+    parser: Parser,
+    unit: ColumnUnit,
+    limits: ParseLimits,
+    (line_index, bytes): (usize, &[u8]),
+) -> Result<Vec<WsvValue>, Error> {
+    let line = match std::str::from_utf8(bytes) {
+        Ok(line) => (line_index, line),
+        Err(e) => {
+            let col = chars_in(bytes) + 1;
+            let error = Error::new(ErrorKind::InvalidUtf8, line_index + 1, col, Some(e.into()));
+            return rejected(error.in_unit(&String::from_utf8_lossy(bytes), unit));
+        }
+    };
+    parser
+        .parse_line_with_limits(line, limits)
+        .map_err(|e| match unit {
+            ColumnUnit::Chars => e.with_fixes(line.1),
+            _ => e.with_fixes(line.1).in_unit(line.1, unit),
+        })
 }
 
-fn from_string_with_parser(i: &mut impl Read, parser: Parser) -> Vec<Result<Vec<WsvValue>, Error>> {
-    let mut buf = String::new();
-    i.read_to_string(&mut buf).unwrap();
-    buf.split('\n').enumerate().map(parser.fn_ptr()).collect()
+/// A row turned away before it reached the parser, so that its error is still traced.
+fn rejected(error: Error) -> Result<Vec<WsvValue>, Error> {
+    let out = Err(error);
+    telemetry::errors(&out);
+    out
 }
 
-pub fn calculate_stuff(input: &Vec<Result<Vec<WsvValue>, Error>>) -> String {
+pub fn calculate_stuff(input: &[Result<Vec<WsvValue>, Error>]) -> String {
     fn char_len(row: &Result<Vec<WsvValue>, Error>) -> usize {
         match row {
             Ok(data) => data.iter().map(|val| val.len()).sum(),
@@ -42,8 +183,8 @@ pub fn calculate_stuff(input: &Vec<Result<Vec<WsvValue>, Error>>) -> String {
         }
     }
 
-    let character_count = input.iter().map(|row| char_len(row)).sum::<usize>();
-    let used_cell_count = input.iter().map(|row| row_len(row)).sum::<usize>();
+    let character_count = input.iter().map(char_len).sum::<usize>();
+    let used_cell_count = input.iter().map(row_len).sum::<usize>();
     let row_count = input.len();
 
     use std::fmt::Write;
@@ -8,8 +8,7 @@ mod unit_tests;
 pub use first::parse_line;
 
 mod data_model;
-pub use data_model::*; // This does not override the pub(crate) declaration of the Parser enum, nor does it throw an error. The glob only takes the pub items, Meaning you can safely use globs in re-exports, since the item will be accessible anyway.
-//pub use crate::data_model::Parser; //trying to expose it explicitly causes an error, as expected.
+pub use data_model::*; // This does not override a pub(crate) declaration, nor does it throw an error. The glob only takes the pub items, Meaning you can safely use globs in re-exports, since the item will be accessible anyway.
 
 
 // the revelation here is that I can comfortably use glob re-exports, meaning I can define upfront the access to modules.
@@ -18,18 +17,65 @@ mod io;
 #[doc(inline)]
 pub use io::*;
 
+mod fixes;
+pub use fixes::*;
+
+mod limits;
+pub use limits::*;
+
+mod registry;
+pub use registry::*;
+
+mod telemetry;
+
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+
+#[cfg(feature = "rkyv")]
+pub mod archive;
+
+pub mod differential;
+
+pub mod generate;
+
+pub mod conformance;
+
+pub mod machine;
+
+pub mod trace;
+
 mod parsers {
+    #[cfg(feature = "chumsky")]
+    pub mod chumsky;
+    #[cfg(feature = "combine")]
+    pub mod combine;
     pub mod first;
+    #[cfg(feature = "lalrpop")]
+    pub mod lalrpop;
+    #[cfg(feature = "logos")]
+    pub mod logos;
     pub mod mealy;
     pub mod moore;
     #[cfg(feature = "nom")]
     pub mod nom;
+    #[cfg(feature = "parol")]
+    pub mod parol;
+    #[cfg(feature = "peg")]
+    pub mod peg;
+    #[cfg(feature = "peginator")]
+    pub mod peginator;
     #[cfg(feature = "pest")]
     pub mod pest;
+    #[cfg(feature = "pom")]
+    pub mod pom;
     pub mod regex;
     pub mod split;
     pub mod state;
-    //pub mod typestate;
+    #[cfg(feature = "tree-sitter")]
+    pub mod tree_sitter;
+    pub mod typestate;
+    #[cfg(feature = "winnow")]
+    pub mod winnow;
 }
 
 // looks like globs work for modules too! This bit of code allows you to control the hierarchy very easily.
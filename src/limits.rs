@@ -0,0 +1,403 @@
+//! Limits on how much of an input the parsers will look at, for when the input is untrusted.
+//!
+//! They are enforced by the readers in `io` which take them, and by the `_with_limits` methods of
+//! `WsvParser`, which every `Parser` has. Nothing else checks them: the plain readers, the
+//! `parse`, `parse_strict` and `parse_line` of `WsvParser` and those of each backend module all
+//! accept input of any size, so they are not safe for untrusted input. The limited ones go one
+//! line at a time, and stop at the first breach.
+//!
+//! The readers enforce the row and byte limits as the input is read, so an oversized line is never
+//! fully read into memory. A `&str` is already in memory, so there they only stop the parse. The
+//! cell and value limits are checked on each line before it is parsed, in a single pass which
+//! stops at the first breach and builds nothing, so a row with a million cells or a value of many
+//! megabytes is never built. The line itself is still read whole, though, so only
+//! `max_line_length` and `max_total_bytes` bound the memory a single line can take. A breach is
+//! reported as `ErrorKind::LimitExceeded` at the position where the limit was crossed, with the
+//! limit itself as the source.
+use crate::data_model::*;
+
+/// Every limit is off by default. Lengths are counted in bytes, not including the newline.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ParseLimits {
+    pub max_line_length: Option<usize>,
+    pub max_cells_per_row: Option<usize>,
+    pub max_value_length: Option<usize>,
+    pub max_rows: Option<usize>,
+    pub max_total_bytes: Option<usize>,
+}
+
+impl ParseLimits {
+    /// Checks every limit which applies to a line before it is parsed: its row number, its length,
+    /// how many cells it has and how long their values are.
+    pub fn check_line(&self, (line_index, line): (usize, &str)) -> Result<(), Error> {
+        let row = line_index + 1;
+        self.check_line_bytes(row, line.as_bytes())?;
+        if self.max_cells_per_row.is_none() && self.max_value_length.is_none() {
+            return Ok(());
+        }
+        for (index, (col, len)) in Cells::new(line).enumerate() {
+            if let Some(max) = self.max_cells_per_row {
+                if index >= max {
+                    return Err(breach("max_cells_per_row", max, row, col));
+                }
+            }
+            if let Some(max) = self.max_value_length {
+                if len > max {
+                    return Err(breach("max_value_length", max, row, col));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// As `check_line`, for a line which has not been decoded yet, and may have been cut short.
+    pub(crate) fn check_line_bytes(&self, row: usize, line: &[u8]) -> Result<(), Error> {
+        if let Some(max) = self.max_rows {
+            if row > max {
+                return Err(breach("max_rows", max, row, 1));
+            }
+        }
+        if let Some(max) = self.max_line_length {
+            if line.len() > max {
+                let col = chars_in(&line[..max]) + 1;
+                return Err(breach("max_line_length", max, row, col));
+            }
+        }
+        Ok(())
+    }
+
+    /// How many bytes may be read for the next line, given `total` bytes have been read so far.
+    /// This leaves room for a `\r\n` after a line of maximum length, plus one byte to detect
+    /// going over.
+    pub(crate) fn line_budget(&self, total: usize) -> u64 {
+        let line = self.max_line_length.map_or(u64::MAX, |max| max as u64 + 3);
+        let document = self
+            .max_total_bytes
+            .map_or(u64::MAX, |max| max.saturating_sub(total) as u64 + 1);
+        line.min(document)
+    }
+
+    /// Checks the running byte count, where `start` bytes came before this line, and `line` is its
+    /// raw bytes, including any newline.
+    pub(crate) fn check_total(&self, row: usize, start: usize, line: &[u8]) -> Result<(), Error> {
+        if let Some(max) = self.max_total_bytes {
+            if start + line.len() > max {
+                let allowed = max.saturating_sub(start);
+                let col = chars_in(&line[..allowed]) + 1;
+                return Err(breach("max_total_bytes", max, row, col));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A document parsed one line at a time with `parse_line`, stopping after the first breached limit.
+/// The running byte count is checked before each line, which `parse_line` is left to check itself.
+pub(crate) fn line_by_line(
+    i: &str,
+    limits: ParseLimits,
+    mut parse_line: impl FnMut((usize, &str)) -> Result<Vec<WsvValue>, Error>,
+) -> Vec<Result<Vec<WsvValue>, Error>> {
+    let mut out = vec![];
+    let mut total = 0;
+    for (index, line) in i.split('\n').enumerate() {
+        let with_newline = &i.as_bytes()[total..(total + line.len() + 1).min(i.len())];
+        let result = limits
+            .check_total(index + 1, total, with_newline)
+            .and_then(|_| parse_line((index, line)));
+        total += with_newline.len();
+        if push_until_limit(&mut out, result) {
+            break;
+        }
+    }
+    out
+}
+
+/// Returns whether to stop, which is the case after the first breached limit.
+pub(crate) fn push_until_limit(
+    out: &mut Vec<Result<Vec<WsvValue>, Error>>,
+    result: Result<Vec<WsvValue>, Error>,
+) -> bool {
+    let stop = matches!(&result, Err(e) if e.kind == ErrorKind::LimitExceeded);
+    out.push(result);
+    stop
+}
+
+fn breach(limit: &str, max: usize, row: usize, col: usize) -> Error {
+    Error::new(
+        ErrorKind::LimitExceeded,
+        row,
+        col,
+        Some(format!("{limit} of {max}").into()),
+    )
+}
+
+/// The number of whole characters in some bytes which may have been cut off mid-character.
+pub(crate) fn chars_in(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.chars().count(),
+        Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()])
+            .expect("valid up to here")
+            .chars()
+            .count(),
+    }
+}
+
+/// The cells of a line, each as the column it starts at and the length in bytes of its value once
+/// unescaped. Nothing is built, and the line is only read as far as the cells are asked for.
+struct Cells<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// How many `char`s have been read so far.
+    read: usize,
+}
+
+impl<'a> Cells<'a> {
+    fn new(line: &'a str) -> Self {
+        Cells {
+            chars: line.chars().peekable(),
+            read: 0,
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        self.read += 1;
+        self.chars.next()
+    }
+}
+
+impl Iterator for Cells<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        while self.chars.peek()?.is_whitespace() {
+            self.next_char();
+        }
+        let col = self.read + 1;
+        let mut len = 0;
+        match self.next_char()? {
+            '#' => return None,
+            '"' => {
+                while let Some(c) = self.next_char() {
+                    match (c, self.chars.peek()) {
+                        ('"', Some('"')) => {
+                            self.next_char();
+                            len += 1;
+                        }
+                        ('"', Some('/')) => {
+                            self.next_char();
+                            self.next_char();
+                            len += 1;
+                        }
+                        ('"', _) => break,
+                        (c, _) => len += c.len_utf8(),
+                    }
+                }
+            }
+            first => {
+                len += first.len_utf8();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_whitespace() || c == '#' {
+                        break;
+                    }
+                    self.next_char();
+                    len += c.len_utf8();
+                }
+                // a lone dash is a null, which has no length.
+                if first == '-' && len == 1 {
+                    len = 0;
+                }
+            }
+        }
+        Some((col, len))
+    }
+}
+
+#[cfg(test)]
+mod limits_tests {
+    use super::*;
+    use crate::{Parser, WsvParser};
+
+    fn limited(limits: ParseLimits, line: &str) -> Result<Vec<WsvValue>, Error> {
+        Parser::First.parse_line_with_limits((0, line), limits)
+    }
+
+    fn position(result: Result<Vec<WsvValue>, Error>) -> (ErrorKind, usize, usize) {
+        let e = result.unwrap_err();
+        (e.kind, e.row, e.col)
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        assert!(limited(ParseLimits::default(), "a b c").is_ok());
+    }
+
+    #[test]
+    fn line_length() {
+        let limits = ParseLimits {
+            max_line_length: Some(4),
+            ..Default::default()
+        };
+        assert!(limited(limits, "abcd").is_ok());
+        assert_eq!(
+            position(limited(limits, "aéb c")),
+            (ErrorKind::LimitExceeded, 1, 4)
+        );
+    }
+
+    #[test]
+    fn cells_per_row() {
+        let limits = ParseLimits {
+            max_cells_per_row: Some(2),
+            ..Default::default()
+        };
+        assert!(limited(limits, "a b # c d").is_ok());
+        assert_eq!(
+            position(limited(limits, r#""a ""b" -  "c d" e"#)),
+            (ErrorKind::LimitExceeded, 1, 12)
+        );
+    }
+
+    #[test]
+    fn value_length() {
+        let limits = ParseLimits {
+            max_value_length: Some(3),
+            ..Default::default()
+        };
+        assert!(limited(limits, r#"abc """""""#).is_ok());
+        assert_eq!(
+            position(limited(limits, r#"abc  "a"/"bc""#)),
+            (ErrorKind::LimitExceeded, 1, 6)
+        );
+    }
+
+    /// The pre-parse scan has to see the same cells as the parsers do, or the limits would be off.
+    #[test]
+    fn cells_agree_with_the_parser() {
+        let shape = crate::generate::Shape {
+            rows: 200,
+            ..Default::default()
+        };
+        for seed in 0..20 {
+            let document = shape.document(seed);
+            for (line, row) in document.text.split('\n').zip(document.rows) {
+                let scanned = Cells::new(line).map(|(_, len)| len).collect::<Vec<_>>();
+                let parsed = row.unwrap().iter().map(WsvValue::len).collect::<Vec<_>>();
+                assert_eq!(scanned, parsed, "{line:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rows() {
+        let limits = ParseLimits {
+            max_rows: Some(1),
+            ..Default::default()
+        };
+        assert!(limits.check_line((0, "a")).is_ok());
+        assert_eq!(
+            position(limits.check_line((1, "a")).map(|_| vec![])),
+            (ErrorKind::LimitExceeded, 2, 1)
+        );
+    }
+
+    #[test]
+    fn reader_stops_on_an_endless_line() {
+        let limits = ParseLimits {
+            max_line_length: Some(1000),
+            ..Default::default()
+        };
+        let out = crate::from_reader_with_limits(&mut std::io::repeat(b'a'), limits);
+        assert_eq!(out.len(), 1);
+        assert_eq!(
+            position(out.into_iter().next().unwrap()),
+            (ErrorKind::LimitExceeded, 1, 1001)
+        );
+    }
+
+    #[test]
+    fn total_bytes() {
+        let limits = ParseLimits {
+            max_total_bytes: Some(6),
+            ..Default::default()
+        };
+        for out in [
+            crate::from_reader_with_limits(&mut "ab\ncd\nef".as_bytes(), limits),
+            crate::from_string_with_limits(&mut "ab\ncd\nef".as_bytes(), limits),
+        ] {
+            assert_eq!(out.len(), 3);
+            assert_eq!(
+                position(out.into_iter().nth(2).unwrap()),
+                (ErrorKind::LimitExceeded, 3, 1)
+            );
+        }
+        let out = crate::from_string_with_limits(&mut "ab\ncdef".as_bytes(), limits);
+        assert_eq!(
+            position(out.into_iter().nth(1).unwrap()),
+            (ErrorKind::LimitExceeded, 2, 4)
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_error_row() {
+        let limits = ParseLimits {
+            max_line_length: Some(1000),
+            ..Default::default()
+        };
+        let mut file = std::fs::File::open("./tests/example_files/invalid_utf8.wsv").unwrap();
+        let out = crate::from_reader_with_limits(&mut file, limits);
+        assert_eq!(
+            position(out.into_iter().next().unwrap()),
+            (ErrorKind::InvalidUtf8, 1, 18)
+        );
+
+        // the rows either side are still read.
+        let input = b"a\nb \xC3\x28\nc";
+        for out in [
+            crate::from_reader_with_limits(&mut input.as_slice(), limits),
+            crate::from_string_with_limits(&mut input.as_slice(), limits),
+        ] {
+            assert_eq!(out.len(), 3);
+            assert!(out[0].is_ok() && out[2].is_ok());
+            assert_eq!(
+                position(out.into_iter().nth(1).unwrap()),
+                (ErrorKind::InvalidUtf8, 2, 3)
+            );
+        }
+    }
+
+    #[test]
+    fn every_parser_stops_at_a_limit() {
+        let limits = ParseLimits {
+            max_cells_per_row: Some(2),
+            max_total_bytes: Some(20),
+            ..Default::default()
+        };
+        for parser in Parser::ALL {
+            let out = parser.parse_with_limits("a b\nc d e\nf", limits);
+            assert_eq!(out.len(), 2, "{parser}");
+            assert!(out[0].is_ok(), "{parser}");
+            assert_eq!(
+                position(out.into_iter().nth(1).unwrap()),
+                (ErrorKind::LimitExceeded, 2, 5),
+                "{parser}"
+            );
+            let strict = parser.parse_strict_with_limits("a\nb\n0123456789abcdefg", limits);
+            assert_eq!(
+                position(strict.map(|_| vec![])),
+                (ErrorKind::LimitExceeded, 3, 17),
+                "{parser}"
+            );
+        }
+    }
+
+    #[test]
+    fn reader_stops_after_max_rows() {
+        let limits = ParseLimits {
+            max_rows: Some(2),
+            ..Default::default()
+        };
+        let out = crate::from_reader_with_limits(&mut "a\nb\nc\nd".as_bytes(), limits);
+        assert_eq!(out.len(), 3);
+        assert!(out[1].is_ok());
+    }
+}
@@ -0,0 +1,259 @@
+//! Drawing a machine as a graph, in Graphviz's DOT or in Mermaid.
+//!
+//! A `match` with a hundred arms is hard to picture, and harder to check. A machine which can list
+//! its states, and sort its inputs into classes it treats alike, can be run once from every state
+//! on one example of every class, and that is the whole graph. States which hold data, like
+//! `Value(char)` in the Moore machine, are drawn as one node by giving them the same name.
+//!
+//! Once it is a graph, it can also be checked for states which can never be reached, or which can
+//! never be left. The final state is always a dead end, so anything else in that list is a bug.
+use std::fmt::Write;
+
+use super::{Mealy, Moore};
+
+/// A machine which can list what it is made of, so it can be drawn.
+pub trait Enumerable {
+    type State;
+    type Input;
+    type Output;
+    /// Every state, or one of each kind where a state holds data.
+    fn states() -> Vec<Self::State>;
+    /// Inputs the machine treats alike, each with a name and one example.
+    fn input_classes() -> Vec<(&'static str, Self::Input)>;
+    /// The name of a node. States which should be drawn as one share a name.
+    fn state_name(state: &Self::State) -> String;
+    fn output_name(output: &Self::Output) -> String;
+}
+
+/// A node, along with its output if it came from a Moore machine.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    pub name: String,
+    pub output: Option<String>,
+}
+
+/// Every transition between the same two nodes with the same output, as one arrow.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub inputs: Vec<&'static str>,
+    /// The output of a Mealy machine, which belongs on the transition rather than the node.
+    pub output: Option<String>,
+}
+
+/// A machine as a graph. The first node is the initial state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagram {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Diagram {
+    pub fn mealy<M>() -> Diagram
+    where
+        M: Mealy
+            + Enumerable<State = M::StateSpace, Input = M::InputAlphabet, Output = M::OutputAlphabet>,
+    {
+        Diagram::build::<M>(
+            M::transition,
+            |_| None,
+            |to, input| Some(M::output_name(&<M as Mealy>::output(to, input))),
+        )
+    }
+
+    pub fn moore<M>() -> Diagram
+    where
+        M: Moore
+            + Enumerable<State = M::StateSpace, Input = M::InputAlphabet, Output = M::OutputAlphabet>,
+    {
+        Diagram::build::<M>(
+            M::transition,
+            |state| Some(M::output_name(&<M as Moore>::output(state))),
+            |_, _| None,
+        )
+    }
+
+    fn build<M: Enumerable>(
+        transition: impl Fn(&M::State, &M::Input) -> M::State,
+        node_output: impl Fn(&M::State) -> Option<String>,
+        edge_output: impl Fn(&M::State, &M::Input) -> Option<String>,
+    ) -> Diagram
+    where
+        M::State: Default,
+    {
+        let mut diagram = Diagram {
+            nodes: vec![],
+            edges: vec![],
+        };
+        let initial = M::State::default();
+        diagram.node::<M>(&initial, &node_output);
+        for from in M::states() {
+            let from_index = diagram.node::<M>(&from, &node_output);
+            for (input_name, input) in M::input_classes() {
+                let to = transition(&from, &input);
+                let to_index = diagram.node::<M>(&to, &node_output);
+                diagram.edge(from_index, to_index, input_name, edge_output(&to, &input));
+            }
+        }
+        diagram
+    }
+
+    /// The index of the node for `state`, which is added if it is not there yet.
+    fn node<M: Enumerable>(
+        &mut self,
+        state: &M::State,
+        output: impl Fn(&M::State) -> Option<String>,
+    ) -> usize {
+        let name = M::state_name(state);
+        match self.nodes.iter().position(|node| node.name == name) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(Node {
+                    name,
+                    output: output(state),
+                });
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn edge(&mut self, from: usize, to: usize, input: &'static str, output: Option<String>) {
+        let existing = self
+            .edges
+            .iter_mut()
+            .find(|edge| (edge.from, edge.to, &edge.output) == (from, to, &output));
+        match existing {
+            Some(edge) if edge.inputs.contains(&input) => {}
+            Some(edge) => edge.inputs.push(input),
+            None => self.edges.push(Edge {
+                from,
+                to,
+                inputs: vec![input],
+                output,
+            }),
+        }
+    }
+
+    /// Nodes with no path to them from the initial state.
+    pub fn unreachable(&self) -> Vec<&str> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            if !std::mem::replace(&mut seen[node], true) {
+                stack.extend(self.edges.iter().filter(|e| e.from == node).map(|e| e.to));
+            }
+        }
+        self.names_where(|index| !seen[index])
+    }
+
+    /// Nodes with no way out to any other node.
+    pub fn dead_ends(&self) -> Vec<&str> {
+        self.names_where(|index| {
+            !self
+                .edges
+                .iter()
+                .any(|edge| edge.from == index && edge.to != index)
+        })
+    }
+
+    fn names_where(&self, keep: impl Fn(usize) -> bool) -> Vec<&str> {
+        (0..self.nodes.len())
+            .filter(|&index| keep(index))
+            .map(|index| self.nodes[index].name.as_str())
+            .collect()
+    }
+
+    fn node_label(&self, index: usize) -> String {
+        let node = &self.nodes[index];
+        match &node.output {
+            Some(output) => format!("{} / {output}", node.name),
+            None => node.name.clone(),
+        }
+    }
+
+    fn edge_label(edge: &Edge) -> String {
+        let inputs = edge.inputs.join(", ");
+        match &edge.output {
+            Some(output) => format!("{inputs} / {output}"),
+            None => inputs,
+        }
+    }
+
+    /// The graph in Graphviz's DOT, for `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let escape = |s: String| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::from("digraph {\n    rankdir=LR;\n    node [shape=box];\n");
+        out.push_str("    start [shape=point];\n    start -> s0;\n");
+        for index in 0..self.nodes.len() {
+            let label = escape(self.node_label(index));
+            writeln!(out, "    s{index} [label=\"{label}\"];").unwrap();
+        }
+        for edge in &self.edges {
+            let label = escape(Diagram::edge_label(edge));
+            writeln!(
+                out,
+                "    s{} -> s{} [label=\"{label}\"];",
+                edge.from, edge.to
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// The graph as a Mermaid state diagram, which GitHub draws inside a `mermaid` code block.
+    pub fn to_mermaid(&self) -> String {
+        // Mermaid has no escapes for these inside a label, so they are swapped for lookalikes.
+        let escape = |s: String| s.replace('"', "'").replace(':', "∶").replace('#', "♯");
+        let mut out = String::from("stateDiagram-v2\n    [*] --> s0\n");
+        for index in 0..self.nodes.len() {
+            let label = escape(self.node_label(index));
+            writeln!(out, "    state \"{label}\" as s{index}").unwrap();
+        }
+        for edge in &self.edges {
+            let label = escape(Diagram::edge_label(edge));
+            writeln!(out, "    s{} --> s{} : {label}", edge.from, edge.to).unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod diagram_tests {
+    use super::*;
+
+    /// Every state a transition leads to is one the machine listed, or it would be drawn without
+    /// any way out of it.
+    fn lists_every_state<M: Enumerable>(diagram: &Diagram) {
+        let listed = M::states().iter().map(M::state_name).collect::<Vec<_>>();
+        for node in &diagram.nodes {
+            assert!(listed.contains(&node.name), "{} is not listed", node.name);
+        }
+    }
+
+    #[test]
+    fn wsv_mealy() {
+        let diagram = Diagram::mealy::<crate::mealy::WsvMachine>();
+        lists_every_state::<crate::mealy::WsvMachine>(&diagram);
+        assert_eq!(diagram.nodes[0].name, "Default");
+        assert!(diagram.unreachable().is_empty());
+        assert_eq!(diagram.dead_ends(), ["Finished"]);
+        assert!(diagram
+            .to_dot()
+            .contains("[label=\"quote / IncrementColumnNumber\"]"));
+    }
+
+    #[test]
+    fn wsv_moore() {
+        let diagram = Diagram::moore::<crate::moore::WsvMachine>();
+        lists_every_state::<crate::moore::WsvMachine>(&diagram);
+        assert!(diagram.unreachable().is_empty());
+        assert_eq!(diagram.dead_ends(), ["Finished"]);
+        assert!(diagram
+            .nodes
+            .iter()
+            .any(|node| node.name == "Value" && node.output.as_deref() == Some("PushChar")));
+        assert!(diagram.to_mermaid().starts_with("stateDiagram-v2\n"));
+    }
+}
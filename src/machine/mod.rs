@@ -0,0 +1,266 @@
+//! Mealy and Moore machines, as close to their textbook definitions as I could get them.
+//!
+//! Each is a sextuple: a set of states with an initial one, an input alphabet, an output alphabet,
+//! a transition function and an output function. Here the sets are types, the initial state is
+//! `Default`, and the functions are associated functions, with no `self`, since a pure state
+//! machine holds no data of its own. Notice how short `process` is. Pure state machines are simple.
+//! The hard part is working out what part of your problem fits in the `StateSpace`,
+//! `InputAlphabet` and `OutputAlphabet`. The functions on them are the easy bit, relatively
+//! speaking.
+//!
+//! The only difference between the two is what the output function looks at. A Moore machine's
+//! output depends only on the state it has just moved into, while a Mealy machine also gets to
+//! look at the input which moved it there. That means a Mealy machine can get by with far fewer
+//! states, and the two can be turned into one another with `MooreAsMealy` and `MealyAsMoore`.
+//!
+//! The WSV parsers in `wsv::mealy` and `wsv::moore` are the worked examples. Their machines are
+//! public, so they can be stepped through one character at a time:
+//!
+//! ```
+//! use wsv::machine::Mealy;
+//! use wsv::mealy::{State, Transform, WsvMachine};
+//!
+//! let mut machine = WsvMachine::stepper();
+//! let step = machine.step(Some('-'));
+//! assert_eq!((step.from, step.to), (State::Default, State::MayBeNull));
+//! assert_eq!(machine.step(None).output, Transform::AddNull);
+//! ```
+use std::marker::PhantomData;
+
+mod diagram;
+pub use diagram::*;
+
+/// The output is decided by the state just moved into, along with the input that moved it there.
+pub trait Mealy {
+    type StateSpace: Default;
+    type InputAlphabet;
+    type OutputAlphabet;
+    fn transition(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::StateSpace;
+    fn output(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::OutputAlphabet;
+
+    /// Runs the machine from its initial state over the whole input, lazily. One can even argue
+    /// for `IntoIterator` here, so that is what it takes.
+    fn process(
+        input: impl IntoIterator<Item = Self::InputAlphabet>,
+    ) -> impl Iterator<Item = Self::OutputAlphabet> {
+        input
+            .into_iter()
+            .scan(Self::StateSpace::default(), |state, i| {
+                *state = Self::transition(state, &i);
+                Some(Self::output(state, &i))
+            })
+    }
+
+    /// A machine in its initial state, to be fed one input at a time.
+    fn stepper() -> Stepper<Self>
+    where
+        Self: Sized,
+    {
+        Stepper {
+            state: Self::StateSpace::default(),
+        }
+    }
+}
+
+/// The output is decided by the state just moved into, and nothing else.
+pub trait Moore {
+    type StateSpace: Default;
+    type InputAlphabet;
+    type OutputAlphabet;
+    fn transition(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::StateSpace;
+    fn output(state: &Self::StateSpace) -> Self::OutputAlphabet;
+
+    /// Runs the machine from its initial state over the whole input, lazily.
+    fn process(
+        input: impl IntoIterator<Item = Self::InputAlphabet>,
+    ) -> impl Iterator<Item = Self::OutputAlphabet> {
+        input
+            .into_iter()
+            .scan(Self::StateSpace::default(), |state, i| {
+                *state = Self::transition(state, &i);
+                Some(Self::output(state))
+            })
+    }
+
+    /// A machine in its initial state, to be fed one input at a time. It steps as the equivalent
+    /// Mealy machine, which gives exactly the same outputs.
+    fn stepper() -> Stepper<MooreAsMealy<Self>>
+    where
+        Self: Sized,
+    {
+        MooreAsMealy::<Self>::stepper()
+    }
+}
+
+/// One transition, with everything that went into it and came out of it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Step<S, I, O> {
+    pub from: S,
+    pub input: I,
+    pub to: S,
+    pub output: O,
+}
+
+/// A machine part way through its input. The state is only ever changed by `step`.
+pub struct Stepper<M: Mealy> {
+    state: M::StateSpace,
+}
+
+impl<M: Mealy> Stepper<M> {
+    pub fn state(&self) -> &M::StateSpace {
+        &self.state
+    }
+}
+
+impl<M: Mealy> Stepper<M>
+where
+    M::StateSpace: Clone,
+{
+    /// Feeds in one input, and gives back the transition it caused.
+    pub fn step(
+        &mut self,
+        input: M::InputAlphabet,
+    ) -> Step<M::StateSpace, M::InputAlphabet, M::OutputAlphabet> {
+        let to = M::transition(&self.state, &input);
+        let output = M::output(&to, &input);
+        let from = std::mem::replace(&mut self.state, to.clone());
+        Step {
+            from,
+            input,
+            to,
+            output,
+        }
+    }
+
+    /// Feeds in every input, one step at a time.
+    pub fn steps(
+        mut self,
+        input: impl IntoIterator<Item = M::InputAlphabet>,
+    ) -> impl Iterator<Item = Step<M::StateSpace, M::InputAlphabet, M::OutputAlphabet>> {
+        input.into_iter().map(move |i| self.step(i))
+    }
+}
+
+impl<M: Mealy> std::fmt::Debug for Stepper<M>
+where
+    M::StateSpace: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stepper")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+/// A Moore machine seen as a Mealy machine which ignores the input in its output function. This
+/// way round costs nothing, the states are exactly the same.
+pub struct MooreAsMealy<M>(PhantomData<M>);
+
+impl<M: Moore> Mealy for MooreAsMealy<M> {
+    type StateSpace = M::StateSpace;
+    type InputAlphabet = M::InputAlphabet;
+    type OutputAlphabet = M::OutputAlphabet;
+    fn transition(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::StateSpace {
+        M::transition(state, input)
+    }
+    fn output(state: &Self::StateSpace, _input: &Self::InputAlphabet) -> Self::OutputAlphabet {
+        M::output(state)
+    }
+}
+
+/// A Mealy machine seen as a Moore machine. This way round, every state has to remember the
+/// output it was reached with, so there are as many states as there are pairs of the two. The
+/// initial state was not reached with anything, so its output is `None`, and every other is
+/// `Some`.
+pub struct MealyAsMoore<M>(PhantomData<M>);
+
+impl<M: Mealy> Moore for MealyAsMoore<M>
+where
+    M::OutputAlphabet: Clone,
+{
+    type StateSpace = (M::StateSpace, Option<M::OutputAlphabet>);
+    type InputAlphabet = M::InputAlphabet;
+    type OutputAlphabet = Option<M::OutputAlphabet>;
+    fn transition((state, _): &Self::StateSpace, input: &Self::InputAlphabet) -> Self::StateSpace {
+        let next = M::transition(state, input);
+        let output = M::output(&next, input);
+        (next, Some(output))
+    }
+    fn output((_, output): &Self::StateSpace) -> Self::OutputAlphabet {
+        output.clone()
+    }
+}
+
+#[cfg(test)]
+mod machine_tests {
+    use super::*;
+
+    /// Says whether it has seen an odd number of `true`s so far.
+    struct Parity;
+    impl Moore for Parity {
+        type StateSpace = bool;
+        type InputAlphabet = bool;
+        type OutputAlphabet = &'static str;
+        fn transition(state: &bool, input: &bool) -> bool {
+            state ^ input
+        }
+        fn output(state: &bool) -> &'static str {
+            if *state {
+                "odd"
+            } else {
+                "even"
+            }
+        }
+    }
+
+    const INPUT: [bool; 4] = [true, false, true, true];
+
+    #[test]
+    fn process() {
+        let out: Vec<_> = Parity::process(INPUT).collect();
+        assert_eq!(out, ["odd", "odd", "even", "odd"]);
+    }
+
+    #[test]
+    fn conversions_give_the_same_outputs() {
+        let moore: Vec<_> = Parity::process(INPUT).collect();
+        let mealy: Vec<_> = MooreAsMealy::<Parity>::process(INPUT).collect();
+        let back: Vec<_> = MealyAsMoore::<MooreAsMealy<Parity>>::process(INPUT)
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(mealy, moore);
+        assert_eq!(back, moore);
+    }
+
+    #[test]
+    fn stepping() {
+        let mut machine = Parity::stepper();
+        assert!(!machine.state());
+        assert_eq!(
+            machine.step(true),
+            Step {
+                from: false,
+                input: true,
+                to: true,
+                output: "odd"
+            }
+        );
+        assert!(machine.state());
+
+        let outputs: Vec<_> = Parity::stepper().steps(INPUT).map(|s| s.output).collect();
+        assert_eq!(outputs, Parity::process(INPUT).collect::<Vec<_>>());
+    }
+
+    /// The two WSV machines are written separately, but should be the same machine.
+    #[test]
+    fn wsv_moore_and_mealy_agree() {
+        let input = "a - \"b\"\"c\"/\"\" #d\n e".chars().map(Some).chain([None]);
+        let mealy: Vec<_> = crate::mealy::WsvMachine::process(input.clone())
+            .map(|t| format!("{t:?}"))
+            .collect();
+        let moore: Vec<_> = crate::moore::WsvMachine::process(input)
+            .map(|t| format!("{t:?}"))
+            .collect();
+        assert_eq!(mealy, moore);
+    }
+}
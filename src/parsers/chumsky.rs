@@ -0,0 +1,210 @@
+//! This is the wsv.pest grammar again, this time built from chumsky's combinators.
+//!
+//! Chumsky looks a lot like nom and winnow, but it was written with error recovery in mind, so
+//! a parser can carry on past a mistake instead of giving up on the whole document. Here every
+//! line which fails to parse is retried with a more forgiving parser, which keeps the cells up to
+//! the mistake and skips the rest of the line. The error is still reported, and the next line is
+//! parsed as normal, so one pass over a document gives back every row, partial or not, along with
+//! chumsky's `Rich` errors, which list everything that would have been accepted where it failed.
+
+use std::sync::{Arc, LazyLock};
+
+use chumsky::cache::{Cache, Cached};
+use chumsky::{
+    error::{LabelError, RichPattern},
+    prelude::*,
+    Parser,
+};
+
+use crate::data_model::*;
+
+type Extra<'a> = extra::Err<Rich<'a, char>>;
+
+const CLOSING_QUOTE: &str = "closing quote";
+
+/// The combinators are built once and shared, rather than again for every line the readers hand
+/// over. Chumsky's cache, behind its `unstable` feature, lets one parser take inputs of any
+/// lifetime.
+static DOCUMENT: LazyLock<Cache<Document>> = LazyLock::new(Cache::default);
+
+#[derive(Default)]
+struct Document;
+
+impl Cached for Document {
+    type Parser<'a> =
+        Arc<dyn Parser<'a, &'a str, Vec<Vec<WsvValue>>, Extra<'a>> + Send + Sync + 'a>;
+
+    fn make_parser<'a>(self) -> <Self as Cached>::Parser<'a> {
+        Arc::new(document())
+    }
+}
+
+/// Every row which parsed, and the first error on each row which did not.
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    let (rows, errors) = parse_recovered(i);
+    let mut errors = errors.into_iter().peekable();
+    rows.into_iter()
+        .enumerate()
+        .map(|(row_index, row)| {
+            let mut first = None;
+            while let Some(e) = errors.next_if(|e| e.row == row_index + 1) {
+                first.get_or_insert(e);
+            }
+            first.map_or(Ok(row), Err)
+        })
+        .collect()
+}
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    match parse_recovered(i) {
+        (rows, errors) if errors.is_empty() => Ok(rows),
+        (_, errors) => Err(errors.into_iter().next().expect("not empty")),
+    }
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    match parse_recovered(line) {
+        (mut rows, errors) if errors.is_empty() => Ok(rows.remove(0)),
+        (_, errors) => {
+            let mut e = errors.into_iter().next().expect("not empty");
+            e.row += row_index;
+            Err(e)
+        }
+    }
+}
+
+/// Parses the whole document, recovering from every error. Rows with errors in them still come
+/// back, holding whichever cells came before the first error, and the errors come back in order.
+pub fn parse_recovered(i: &str) -> (Vec<Vec<WsvValue>>, Vec<Error>) {
+    let (rows, errors) = DOCUMENT.get().parse(i).into_output_errors();
+    (
+        rows.expect("every line can be recovered"),
+        errors.into_iter().map(|e| to_error(i, e)).collect(),
+    )
+}
+
+/// Chumsky counts spans in bytes from the start of the input, so this turns them into a row and
+/// a column counted in `char`s. The `Rich` error itself is kept as the source.
+///
+/// When several alternatives fail at the end of a line, the span can start wherever the first of
+/// them did. A string which is never closed always runs to the end of its line though, so that is
+/// where those errors are reported.
+fn to_error(input: &str, e: Rich<char>) -> Error {
+    let kind = match e
+        .expected()
+        .any(|pattern| matches!(pattern, RichPattern::Label(label) if label == CLOSING_QUOTE))
+    {
+        true => ErrorKind::OddDoubleQuotes,
+        false => ErrorKind::MissingWhitespace,
+    };
+    let offset = match kind {
+        ErrorKind::OddDoubleQuotes => input[e.span().start..]
+            .find('\n')
+            .map_or(input.len(), |i| e.span().start + i),
+        _ => e.span().start,
+    };
+    let before = &input[..offset];
+    let row = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+    Error::new(kind, row, col, Some(e.to_string().into()))
+}
+
+fn document<'a>() -> impl Parser<'a, &'a str, Vec<Vec<WsvValue>>, Extra<'a>> {
+    let whitespace = any().filter(|c: &char| c.is_whitespace() && *c != '\n');
+    let value_char = any().filter(|c: &char| !(*c == '"' || *c == '#' || c.is_whitespace()));
+
+    let null = just('-').then(value_char.not()).to(WsvValue::Null);
+    let value = value_char
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .map(|s: &str| WsvValue::V(s.to_owned()));
+    let string_part = none_of("\"\n").repeated().at_least(1).to_slice();
+    // Chumsky reports whichever alternative got furthest, so with `just("\"/\"")`, the `""/` of
+    // an empty string followed by a `/` would be blamed on the `"/"` never being finished, one
+    // column after the real mistake. A custom parser puts its error where it started instead, as
+    // an expected label, so that it still merges with the others there.
+    let escaped_return = custom(|inp| {
+        let before = inp.cursor();
+        let found = inp.peek_maybe();
+        match [inp.next(), inp.next(), inp.next()] {
+            [Some('"'), Some('/'), Some('"')] => Ok("\n"),
+            _ => Err(LabelError::<&str, _>::expected_found(
+                ["\"/\""],
+                found,
+                inp.span_since(&before),
+            )),
+        }
+    });
+    let string = just('"')
+        .ignore_then(
+            choice((just("\"\"").to("\""), escaped_return, string_part))
+                .repeated()
+                .collect::<Vec<&str>>(),
+        )
+        .then_ignore(just('"').labelled(CLOSING_QUOTE))
+        .map(|parts| WsvValue::V(parts.concat()));
+    let comment = just('#').then(none_of('\n').repeated());
+
+    let cells = whitespace
+        .repeated()
+        .ignore_then(
+            choice((null, string, value))
+                .separated_by(whitespace.repeated().at_least(1))
+                .collect::<Vec<_>>(),
+        )
+        .then_ignore(whitespace.repeated())
+        .then_ignore(comment.or_not());
+    let line_end = just('\n').ignored().or(end()).rewind();
+
+    let line = cells
+        .clone()
+        .then_ignore(line_end)
+        .recover_with(via_parser(cells.then_ignore(none_of('\n').repeated())));
+
+    line.separated_by(just('\n'))
+        .collect::<Vec<_>>()
+        .then_ignore(end())
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
+
+#[cfg(test)]
+mod chumsky_tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_cells_before_an_error() {
+        let (rows, errors) = parse_recovered("a b\nc \"d\"e f\ng \"h");
+        assert_eq!(
+            rows,
+            vec![
+                vec![WsvValue::from("a"), WsvValue::from("b")],
+                vec![WsvValue::from("c"), WsvValue::from("d")],
+                vec![WsvValue::from("g")],
+            ]
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| (e.kind, e.row, e.col))
+                .collect::<Vec<_>>(),
+            vec![
+                (ErrorKind::MissingWhitespace, 2, 6),
+                (ErrorKind::OddDoubleQuotes, 3, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn one_result_per_row() {
+        let rows = parse("a\n\"\nb");
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].is_ok() && rows[1].is_err() && rows[2].is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+//! This is the nom grammar, ported to combine.
+//!
+//! Combine is a port of Haskell's parsec, so it differs from nom in one way that matters here. An
+//! alternative is only tried if the one before it failed without consuming any input, so once a
+//! string has seen its opening quote, a missing closing quote fails the whole line instead of
+//! quietly backtracking. Anything that needs to look further ahead before committing is wrapped
+//! in `attempt`.
+//!
+//! Run through `easy_parse` on a positioned stream, the error knows its line and column, and what
+//! was found there. The end of a line is always a fine place for a row to stop, so the only thing
+//! that can fail there is a string, which means the quotes were odd.
+
+use combine::{
+    attempt, between, choice, eof, look_ahead, many, many1, none_of, not_followed_by, optional,
+    parser::char::string,
+    satisfy, sep_by, skip_many, skip_many1,
+    stream::{easy, position, position::SourcePosition},
+    token, EasyParser, Parser, Stream,
+};
+
+use crate::data_model::*;
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+/// As with nom, newlines are handled by the grammar here too, rather than by splitting first.
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    sep_by(line(), token('\n'))
+        .skip(eof())
+        .easy_parse(position::Stream::new(i))
+        .map(|(o, _)| o)
+        .map_err(|e| to_error(0, e))
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, input): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    line()
+        .skip(eof())
+        .easy_parse(position::Stream::new(input))
+        .map(|(o, _)| o)
+        .map_err(|e| to_error(row_index, e))
+}
+
+/// combine already counts lines and `char` columns from 1, so only the kind needs working out.
+fn to_error(row_index: usize, e: easy::Errors<char, &str, SourcePosition>) -> Error {
+    let at_end_of_line = e.errors.iter().any(|error| {
+        matches!(
+            error,
+            easy::Error::Unexpected(easy::Info::Token('\n') | easy::Info::Static("end of input"))
+        )
+    });
+    let kind = match at_end_of_line {
+        true => ErrorKind::OddDoubleQuotes,
+        false => ErrorKind::MissingWhitespace,
+    };
+    Error::new(
+        kind,
+        row_index + e.position.line as usize,
+        e.position.column as usize,
+        Some(e.to_string().into()),
+    )
+}
+
+fn is_whitespace(c: char) -> bool {
+    c.is_whitespace() && c != '\n'
+}
+
+/// Whitespace only separates two cells if another cell comes after it. Checking that first means
+/// a cell which fails after the whitespace is reported, rather than ending the line early.
+fn line<Input>() -> impl Parser<Input, Output = Vec<WsvValue>>
+where
+    Input: Stream<Token = char>,
+{
+    let separator =
+        attempt(skip_many1(satisfy(is_whitespace)).skip(look_ahead(none_of("#\n".chars()))));
+    skip_many(satisfy(is_whitespace))
+        .with(sep_by(choice((nul(), string_value(), value())), separator))
+        .skip(skip_many(satisfy(is_whitespace)))
+        .skip(optional(comment()))
+}
+
+fn nul<Input>() -> impl Parser<Input, Output = WsvValue>
+where
+    Input: Stream<Token = char>,
+{
+    attempt(token('-').skip(not_followed_by(value().map(|_| "value")))).map(|_| WsvValue::Null)
+}
+
+fn string_value<Input>() -> impl Parser<Input, Output = WsvValue>
+where
+    Input: Stream<Token = char>,
+{
+    between(
+        token('"'),
+        token('"'),
+        many(choice((
+            none_of("\"\n".chars()),
+            attempt(string("\"\"")).map(|_| '"'),
+            attempt(string("\"/\"")).map(|_| '\n'),
+        ))),
+    )
+    .map(WsvValue::V)
+}
+
+fn value<Input>() -> impl Parser<Input, Output = WsvValue>
+where
+    Input: Stream<Token = char>,
+{
+    many1(satisfy(|c: char| {
+        !(c == '"' || c == '#' || c.is_whitespace())
+    }))
+    .map(WsvValue::V)
+}
+
+fn comment<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream<Token = char>,
+{
+    token('#').with(skip_many(satisfy(|c| c != '\n')))
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
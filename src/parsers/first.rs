@@ -42,6 +42,10 @@ pub fn parse_line((line_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Er
                 if closing_quote_pos.is_none() {
                     buf.push('#');
                 } else {
+                    // a comment can follow a value with no whitespace in between.
+                    if !buf.is_empty() {
+                        values.push(WsvValue::convert(&buf));
+                    }
                     break;
                 }
             }
@@ -52,12 +56,18 @@ pub fn parse_line((line_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Er
                     if buf == "\"" || (buf.starts_with('"') && !buf.ends_with('"')) {
                         return Err(Error::new(ErrorKind::OddDoubleQuotes, row, col + 1, None));
                     }
-                    values.push(parse_value(&mut buf));
+                    values.push(WsvValue::convert(&buf));
                     buf.clear();
                 }
                 // ignore otherwise
             }
             Some('/') => {
+                // right after a closing quote, a slash is only allowed as the middle of `"/"`.
+                if let Some(pos) = closing_quote_pos {
+                    if pos + 1 == col && pos != 0 && chars.clone().next() != Some('"') {
+                        return Err(Error::new(ErrorKind::MissingWhitespace, row, col, None));
+                    }
+                }
                 buf.push('/');
             }
             Some(c) => {
@@ -73,10 +83,10 @@ pub fn parse_line((line_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Er
             }
             None => {
                 if !buf.is_empty() {
-                    if buf == "\"" || (buf.starts_with('"') && !buf.ends_with('"')) {
+                    if closing_quote_pos.is_none() {
                         return Err(Error::new(ErrorKind::OddDoubleQuotes, row, col, None));
                     }
-                    values.push(parse_value(&mut buf));
+                    values.push(WsvValue::convert(&buf));
                 }
                 break;
             }
@@ -85,21 +95,6 @@ pub fn parse_line((line_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Er
     Ok(values)
 }
 
-/// Assume we have a well-formed buf.
-fn parse_value(buf: &mut str) -> WsvValue {
-    if buf == "-" {
-        WsvValue::Null
-    } else if buf.starts_with('"') && buf.ends_with('"') {
-        WsvValue::V(
-            buf[1..buf.len() - 1]
-                .replace("\"/\"", "\n")
-                .replace("\"\"", "\""),
-        )
-    } else {
-        WsvValue::V(buf.into())
-    }
-}
-
 #[cfg(test)]
 use crate::unit;
 #[cfg(test)]
@@ -0,0 +1,193 @@
+//! An LR(1) parser, generated by LALRPOP from the grammar in wsv.lalrpop.
+//!
+//! All the other grammars here are PEGs, which try their alternatives in order and backtrack.
+//! LALRPOP instead builds its parse tables at compile time in `build.rs`, and never backtracks,
+//! so every token is looked at exactly once and any ambiguity in the grammar is a build error.
+//!
+//! LALRPOP can generate a lexer too, but only from regexes over the whole input, and whether a
+//! `#` or a space is part of a value depends on whether we are inside a string. So the lexer
+//! below is written by hand, and the grammar only has to worry about where whitespace goes.
+
+use std::fmt;
+use std::str::Chars;
+
+use lalrpop_util::{lalrpop_mod, ParseError};
+
+use crate::data_model::*;
+
+lalrpop_mod!(grammar, "/parsers/wsv.rs");
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    grammar::DocumentParser::new()
+        .parse(Lexer::new(i))
+        .map_err(|e| to_error(i, 0, e))
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    grammar::LineParser::new()
+        .parse(Lexer::new(line))
+        .map_err(|e| to_error(line, row_index, e))
+}
+
+/// Every location is a byte offset from the start of the input, so this turns it into a row and
+/// a column counted in `char`s. Any token in the wrong place can only be a cell which should have
+/// had whitespace before it. The error itself is kept as the source.
+fn to_error(input: &str, row_index: usize, e: ParseError<usize, Tok, LexicalError>) -> Error {
+    let (kind, offset) = match e {
+        ParseError::User {
+            error: LexicalError::UnclosedString(end),
+        } => (ErrorKind::OddDoubleQuotes, end),
+        ParseError::InvalidToken { location } | ParseError::UnrecognizedEof { location, .. } => {
+            (ErrorKind::MissingWhitespace, location)
+        }
+        ParseError::UnrecognizedToken {
+            token: (start, _, _),
+            ..
+        }
+        | ParseError::ExtraToken {
+            token: (start, _, _),
+        } => (ErrorKind::MissingWhitespace, start),
+    };
+    let before = &input[..offset];
+    let row = row_index + before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+    Error::new(kind, row, col, Some(e.to_string().into()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Tok<'input> {
+    Value(&'input str),
+    String(String),
+    Null,
+    Whitespace,
+    Comment,
+    NewLine,
+}
+
+impl fmt::Display for Tok<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Value(v) => write!(f, "value {v:?}"),
+            Tok::String(s) => write!(f, "string {s:?}"),
+            Tok::Null => write!(f, "null"),
+            Tok::Whitespace => write!(f, "whitespace"),
+            Tok::Comment => write!(f, "comment"),
+            Tok::NewLine => write!(f, "new line"),
+        }
+    }
+}
+
+/// The only thing the lexer can fail on is a string which is still open at the end of its line.
+/// The offset is where the closing quote should have been. The string is still handed to the
+/// grammar first, and the error only comes with the token after it, so that a string with no
+/// whitespace before it is reported as that, which comes first on the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LexicalError {
+    UnclosedString(usize),
+}
+
+impl fmt::Display for LexicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexicalError::UnclosedString(_) => write!(f, "string is never closed"),
+        }
+    }
+}
+
+pub(crate) struct Lexer<'input> {
+    input: &'input str,
+    chars: Chars<'input>,
+    /// The end of a string which was never closed, to be reported as soon as it has been lexed.
+    unclosed: Option<usize>,
+}
+
+impl<'input> Lexer<'input> {
+    pub(crate) fn new(input: &'input str) -> Self {
+        Lexer {
+            input,
+            chars: input.chars(),
+            unclosed: None,
+        }
+    }
+
+    fn rest(&self) -> &'input str {
+        self.chars.as_str()
+    }
+
+    fn offset(&self) -> usize {
+        self.input.len() - self.rest().len()
+    }
+
+    /// Skips ahead while `f` holds, returning the offset of the first character it did not.
+    fn skip_while(&mut self, f: impl Fn(char) -> bool) -> usize {
+        let len = self.rest().find(|c| !f(c)).unwrap_or(self.rest().len());
+        self.chars = self.rest()[len..].chars();
+        self.offset()
+    }
+
+    fn string(&mut self, start: usize) -> (usize, Tok<'input>, usize) {
+        let mut out = String::new();
+        loop {
+            let part_start = self.offset();
+            let part_end = self.skip_while(|c| c != '"' && c != '\n');
+            out.push_str(&self.input[part_start..part_end]);
+            let rest = self.rest();
+            if let Some(rest) = rest.strip_prefix("\"\"") {
+                out.push('"');
+                self.chars = rest.chars();
+            } else if let Some(rest) = rest.strip_prefix("\"/\"") {
+                out.push('\n');
+                self.chars = rest.chars();
+            } else if let Some(rest) = rest.strip_prefix('"') {
+                self.chars = rest.chars();
+                return (start, Tok::String(out), self.offset());
+            } else {
+                self.unclosed = Some(part_end);
+                return (start, Tok::String(out), part_end);
+            }
+        }
+    }
+}
+
+impl<'input> Iterator for Lexer<'input> {
+    type Item = Result<(usize, Tok<'input>, usize), LexicalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(end) = self.unclosed.take() {
+            return Some(Err(LexicalError::UnclosedString(end)));
+        }
+        let start = self.offset();
+        let c = self.chars.next()?;
+        let token = match c {
+            '\n' => Tok::NewLine,
+            '"' => return Some(Ok(self.string(start))),
+            '#' => {
+                self.skip_while(|c| c != '\n');
+                Tok::Comment
+            }
+            c if c.is_whitespace() => {
+                self.skip_while(|c| c != '\n' && c.is_whitespace());
+                Tok::Whitespace
+            }
+            _ => {
+                let end = self.skip_while(|c| !(c == '"' || c == '#' || c.is_whitespace()));
+                match &self.input[start..end] {
+                    "-" => Tok::Null,
+                    value => Tok::Value(value),
+                }
+            }
+        };
+        Some(Ok((start, token, self.offset())))
+    }
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
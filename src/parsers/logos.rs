@@ -0,0 +1,87 @@
+//! A lexer generated by logos, with a small parser on top.
+//!
+//! Every other implementation here does the whole job in one pass. This one splits it in two, the
+//! way most compilers do. Logos compiles the token regexes below into a single DFA, which chops a
+//! line into tokens, and the parser only has to check that the tokens come in a sensible order.
+//! In WSV that boils down to one rule: two cells must have whitespace between them.
+//!
+//! No regex matches a string which never closes, so that is the only thing a lexer error can
+//! mean, and it becomes the `Error` token. Matching it with a regex of its own would not do, since
+//! logos does not backtrack between tokens: given `""/`, such a regex would read the `/` as the
+//! start of a `"/"` and win over the empty string before it, which is how every other parser reads
+//! it.
+
+use logos::Logos;
+
+use crate::data_model::*;
+
+#[derive(Logos, Debug, Copy, Clone, PartialEq, Eq)]
+enum Token {
+    #[regex(r##"[^"#\s]+"##)]
+    Value,
+    #[regex(r##""([^"\n]|""|"/")*""##)]
+    QuotedString,
+    /// A lone dash also matches `Value`, so it needs the higher priority.
+    #[token("-", priority = 3)]
+    Null,
+    #[regex(r"#[^\n]*")]
+    Comment,
+    #[regex(r"[\s&&[^\n]]+")]
+    Whitespace,
+    /// What the lexer gives back when no other token matches.
+    Error,
+}
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    let row = row_index + 1;
+    let col = |byte: usize| line[..byte].chars().count() + 1;
+
+    let mut values = vec![];
+    let mut separated = true;
+    for (token, span) in Token::lexer(line).spanned() {
+        let token = token.unwrap_or(Token::Error);
+        match token {
+            Token::Whitespace => {
+                separated = true;
+                continue;
+            }
+            Token::Comment => break,
+            _ if !separated => {
+                return Err(Error::new(
+                    ErrorKind::MissingWhitespace,
+                    row,
+                    col(span.start),
+                    None,
+                ))
+            }
+            // the missing quote belongs at the end of the line.
+            Token::Error => {
+                return Err(Error::new(
+                    ErrorKind::OddDoubleQuotes,
+                    row,
+                    col(line.len()),
+                    None,
+                ))
+            }
+            Token::Null => values.push(WsvValue::Null),
+            Token::Value => values.push(WsvValue::V(line[span].to_owned())),
+            Token::QuotedString => values.push(WsvValue::convert(&line[span])),
+        }
+        separated = false;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
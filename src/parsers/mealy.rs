@@ -12,6 +12,7 @@
 //! The input set is still this number plus one for the None case.
 
 use crate::data_model::*;
+use crate::machine::{Enumerable, Mealy};
 
 pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
     i.split('\n').enumerate().map(parse_line).collect()
@@ -42,48 +43,15 @@ pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Err
     partially_constructed_wsv.finish_row()
 }
 
-/// This trait encapsulates the raw definition of a Mealy Machine as closely as I can to the wikipedia entry.
-///
-/// There are two functions, three types, and one type constrait, making up the sextuple that is a
-/// Mealy Machine. I am using the Default trait parameter on the StateSpace as the initial state requirement.
-/// Notice that the function to combine all these parts is very short. Pure state machines are simple. The
-/// hard part is working out what part of your problem fits in the StateSpace, InputAlphabet and OutputAlphabet.
-/// The functions on them are the easy bit, relatively speaking.
-trait Mealy {
-    type StateSpace: Default;
-    type InputAlphabet;
-    type OutputAlphabet;
-    fn transition(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::StateSpace;
-    fn output(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::OutputAlphabet;
-
-    // /// This is the stateful version, with in and out being vectors instead.
-    // fn process_vec(input: Vec<Self::InputAlphabet>) -> Vec<Self::OutputAlphabet> {
-    //     let mut state = Self::StateSpace::default();
-    //     let mut output = vec![];
-    //     for i in input {
-    //         state = Self::transition(&state, &i);
-    //         output.push(Self::output(&state, &i));
-    //     }
-    //     output
-    // }
-
-    /// This is what I believe is the more "idiomatic" way. One can even argue for using
-    /// `IntoIterator` instead of the `Iterator` trait.
-    fn process(
-        input: impl Iterator<Item = Self::InputAlphabet>,
-    ) -> impl Iterator<Item = Self::OutputAlphabet> {
-        input.scan(Self::StateSpace::default(), |state, i| {
-            *state = Self::transition(state, &i);
-            Some(Self::output(state, &i))
-        })
-    }
-}
+// The `Mealy` trait itself lives in `crate::machine`, so that it can be used for other formats too.
 
+/// WSV as a Mealy machine. Feed it every `char` of a document as `Some`, then `None` to finish.
 #[derive(Debug, Default)]
-struct WsvMachine {}
+pub struct WsvMachine {}
 
+/// Where the machine is within a document.
 #[derive(Debug, PartialEq, Hash, Clone, Copy, Default)]
-enum State {
+pub enum State {
     Comment,
     #[default]
     Default,
@@ -97,17 +65,21 @@ enum State {
     Finished,
     MayBeEscapedReturn,
     MayBeNull,
+    NotAnEscapedReturn,
     Null,
     NullEndOfLine,
+    NullStartComment,
     StartComment,
     StartString,
     StringPart,
     Value,
 }
 
+/// What the `WsvBuilder` does to the row on each transition.
 #[derive(Debug, PartialEq, Clone, Copy, Hash)]
-enum Transform {
+pub enum Transform {
     AddError(ErrorKind),
+    AddErrorBefore(ErrorKind),
     AddNull,
     AddNullAndRow,
     AddRow,
@@ -132,12 +104,16 @@ impl Mealy for WsvMachine {
             (State::StringPart, Some(c)) => Transform::PushChar(*c),
             (State::StringPart, _) => Transform::IncrementColumnNumber,
             (State::Error(kind), _) => Transform::AddError(*kind),
+            (State::NotAnEscapedReturn, _) => {
+                Transform::AddErrorBefore(ErrorKind::MissingWhitespace)
+            }
             (State::MayBeNull, _) => Transform::PushDash,
             (State::EscapedReturn, _) => Transform::PushNewline,
             (State::EscapedDoubleQuote, _) => Transform::PushQuote,
             (State::EndOfValue, _) => Transform::AddValue,
             (State::StartComment, _) => Transform::AddValue,
             (State::Null, _) => Transform::AddNull,
+            (State::NullStartComment, _) => Transform::AddNull,
             (State::EndOfLine, _) => Transform::AddRow,
             (State::NullEndOfLine, _) => Transform::AddNullAndRow,
             (State::EndOfValueAndEndOfLine, _) => Transform::AddValueAndRow,
@@ -157,6 +133,9 @@ impl Mealy for WsvMachine {
             (State::StartComment, Some('\n')) => State::EndOfLine,
             (State::StartComment, None) => State::Finished,
             (State::StartComment, _) => State::Comment,
+            (State::NullStartComment, Some('\n')) => State::EndOfLine,
+            (State::NullStartComment, None) => State::Finished,
+            (State::NullStartComment, _) => State::Comment,
 
             (State::EndOfValueAndEndOfLine, None) => State::Finished,
             (State::EndOfValueAndEndOfLine, Some('\n')) => State::EndOfLine,
@@ -200,7 +179,7 @@ impl Mealy for WsvMachine {
 
             (State::Null, None) => State::Finished,
             (State::Null, Some('\n')) => State::EndOfLine,
-            (State::Null, Some('#')) => State::EndOfLine,
+            (State::Null, Some('#')) => State::Comment,
             (State::Null, Some('-')) => State::MayBeNull,
             (State::Null, Some('\"')) => State::StartString,
             (State::Null, Some(c)) if c.is_whitespace() => State::Default,
@@ -210,6 +189,7 @@ impl Mealy for WsvMachine {
             (State::MayBeNull, Some('\n')) => State::NullEndOfLine,
             (State::MayBeNull, Some(c)) if c.is_whitespace() => State::Null,
             (State::MayBeNull, Some('\"')) => State::Error(ErrorKind::MissingWhitespace),
+            (State::MayBeNull, Some('#')) => State::NullStartComment,
             (State::MayBeNull, Some(_)) => State::Value,
 
             (State::Value, None) => State::EndOfValue,
@@ -243,7 +223,8 @@ impl Mealy for WsvMachine {
             (State::EscapeOrEndOfString, _) => State::Error(ErrorKind::MissingWhitespace),
 
             (State::MayBeEscapedReturn, Some('\"')) => State::EscapedReturn,
-            (State::MayBeEscapedReturn, _) => State::Error(ErrorKind::MissingWhitespace),
+            (State::MayBeEscapedReturn, _) => State::NotAnEscapedReturn,
+            (State::NotAnEscapedReturn, _) => State::Finished,
 
             (State::StringPart, None) => State::Error(ErrorKind::OddDoubleQuotes),
             (State::StringPart, Some('\n')) => State::Error(ErrorKind::OddDoubleQuotes),
@@ -253,6 +234,107 @@ impl Mealy for WsvMachine {
     }
 }
 
+/// Every state the machine can be in. None of them hold data, so this is the whole enum, less
+/// the error kinds which only other parsers report.
+const STATES: [State; 21] = [
+    State::Comment,
+    State::Default,
+    State::EndOfLine,
+    State::EndOfValue,
+    State::EndOfValueAndEndOfLine,
+    State::Error(ErrorKind::OddDoubleQuotes),
+    State::Error(ErrorKind::MissingWhitespace),
+    State::EscapeOrEndOfString,
+    State::EscapedReturn,
+    State::EscapedDoubleQuote,
+    State::Finished,
+    State::MayBeEscapedReturn,
+    State::MayBeNull,
+    State::NotAnEscapedReturn,
+    State::Null,
+    State::NullEndOfLine,
+    State::NullStartComment,
+    State::StartComment,
+    State::StartString,
+    State::StringPart,
+    State::Value,
+];
+
+/// Where a state is in `STATES`, or `None` for an error this machine never reports. There is
+/// no wildcard, so a new state or error kind will not compile until it is placed.
+const fn position(state: State) -> Option<usize> {
+    Some(match state {
+        State::Comment => 0,
+        State::Default => 1,
+        State::EndOfLine => 2,
+        State::EndOfValue => 3,
+        State::EndOfValueAndEndOfLine => 4,
+        State::Error(ErrorKind::OddDoubleQuotes) => 5,
+        State::Error(ErrorKind::MissingWhitespace) => 6,
+        State::Error(ErrorKind::Nom | ErrorKind::LimitExceeded | ErrorKind::InvalidUtf8) => {
+            return None
+        }
+        State::EscapeOrEndOfString => 7,
+        State::EscapedReturn => 8,
+        State::EscapedDoubleQuote => 9,
+        State::Finished => 10,
+        State::MayBeEscapedReturn => 11,
+        State::MayBeNull => 12,
+        State::NotAnEscapedReturn => 13,
+        State::Null => 14,
+        State::NullEndOfLine => 15,
+        State::NullStartComment => 16,
+        State::StartComment => 17,
+        State::StartString => 18,
+        State::StringPart => 19,
+        State::Value => 20,
+    })
+}
+
+// Checked as the crate compiles: each entry of `STATES` sits where `position` puts it.
+const _: () = {
+    let mut index = 0;
+    while index < STATES.len() {
+        assert!(matches!(position(STATES[index]), Some(place) if place == index));
+        index += 1;
+    }
+};
+
+/// Drawn with `crate::machine::Diagram`, with the output of each transition on its arrow.
+impl Enumerable for WsvMachine {
+    type State = State;
+    type Input = Option<char>;
+    type Output = Transform;
+    fn states() -> Vec<State> {
+        STATES.to_vec()
+    }
+    fn input_classes() -> Vec<(&'static str, Option<char>)> {
+        INPUT_CLASSES.to_vec()
+    }
+    fn state_name(state: &State) -> String {
+        format!("{state:?}")
+    }
+    fn output_name(output: &Transform) -> String {
+        match output {
+            Transform::PushChar(_) => "PushChar".to_owned(),
+            _ => format!("{output:?}"),
+        }
+    }
+}
+
+/// `Option<char>` sorted into the classes the machine can tell apart. The Moore machine reads
+/// the same input, so it uses these too.
+pub(crate) const INPUT_CLASSES: [(&str, Option<char>); 8] = [
+    ("end", None),
+    ("newline", Some('\n')),
+    ("hash", Some('#')),
+    ("dash", Some('-')),
+    ("quote", Some('"')),
+    ("slash", Some('/')),
+    ("whitespace", Some(' ')),
+    ("other", Some('a')),
+];
+
 #[derive(Debug)]
 struct WsvBuilder {
     row: usize,
@@ -286,6 +368,10 @@ impl WsvBuilder {
                 self.col += 1;
                 self.err = Some(Error::new(kind, self.row, self.col, None));
             }
+            // the `/` after a string turned out to start a cell with no whitespace before it.
+            Transform::AddErrorBefore(kind) => {
+                self.err = Some(Error::new(kind, self.row, self.col, None));
+            }
             Transform::PushDash => {
                 self.col += 1;
                 self.buf.push('-');
@@ -316,43 +402,41 @@ impl WsvBuilder {
             }
             Transform::IncrementColumnNumber => self.col += 1,
             Transform::AddValueAndRow => {
-                self.col += 1;
-
                 self.out
                     .last_mut()
                     .expect("initialised with one")
                     .push(WsvValue::V(self.buf.clone()));
-                self.out.push(vec![]);
+                self.add_row();
 
                 self.buf.clear();
             }
             Transform::AddNullAndRow => {
-                self.col += 1;
-
                 self.out
                     .last_mut()
                     .expect("initialised with one")
                     .push(WsvValue::Null);
-                self.out.push(vec![]);
+                self.add_row();
 
                 self.buf.clear();
             }
-            Transform::AddRow => {
-                self.col += 1;
-
-                self.out.push(vec![])
-            }
+            Transform::AddRow => self.add_row(),
         }
     }
+    /// Errors on the next row are counted from its own first column.
+    fn add_row(&mut self) {
+        self.row += 1;
+        self.col = 0;
+        self.out.push(vec![]);
+    }
     fn finish(self) -> Result<Vec<Vec<WsvValue>>, Error> {
         match self.err {
-            Some(e) => Err(dbg!(e)),
+            Some(e) => Err(e),
             None => Ok(self.out),
         }
     }
     fn finish_row(mut self) -> Result<Vec<WsvValue>, Error> {
         match self.err {
-            Some(e) => Err(dbg!(e)),
+            Some(e) => Err(e),
             None => Ok(self.out.pop().unwrap()),
         }
     }
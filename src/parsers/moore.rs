@@ -4,6 +4,7 @@
 //! The input set is still this number plus one for the None case.
 
 use crate::data_model::*;
+use crate::machine::{Enumerable, Moore};
 
 pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
     i.split('\n').enumerate().map(parse_line).collect()
@@ -25,7 +26,7 @@ pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
 pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
     let input_set = line.chars().map(Some).chain(vec![None]);
 
-    let mut partially_constructed_wsv = WsvBuilder::new().at_row(row_index + 1);
+    let mut partially_constructed_wsv = WsvBuilder::new().at_row(row_index);
     WsvMachine::process(input_set).for_each(|transform| {
         partially_constructed_wsv.apply(transform);
     });
@@ -33,48 +34,19 @@ pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Err
     partially_constructed_wsv.finish_row()
 }
 
-/// This trait encapsulates the raw definition of a Moore Machine as closely as I can to the wikipedia entry.
-///
-/// There are two functions, three types, and one type constrait, making up the sextuple that is a Moore Machine. I am using the Default trait parameter on the StateSpace as the initial state requirement.Notice that the function to combine all these parts is very short. Stateless machines are simple. The hard part is working out what part of your problem fits in the StateSpace, InputAlphabet and OutputAlphabet. The functions on them are the easy bit, relatively speaking. The only difference with Mealy here is the parametrisation of the output function.
-trait Moore {
-    type StateSpace: Default;
-    type InputAlphabet;
-    type OutputAlphabet;
-    fn transition(state: &Self::StateSpace, input: &Self::InputAlphabet) -> Self::StateSpace;
-    fn output(state: &Self::StateSpace) -> Self::OutputAlphabet;
-
-    // /// This is the stateful version, with in and out being vectors instead.
-    // fn process_vec(input: Vec<Self::InputAlphabet>) -> Vec<Self::OutputAlphabet> {
-    //     let mut state = Self::StateSpace::default();
-    //     let mut output = vec![];
-    //     for i in input {
-    //         state = Self::transition(&state, &i);
-    //         output.push(Self::output(&state));
-    //     }
-    //     output
-    // }
-
-    /// This is is the stateless version, and could even be implemented as a dedicated iterator adapter. We could also use `IntoIterator` instead of the `Iterator` trait to be more flexible.
-    fn process(
-        input: impl Iterator<Item = Self::InputAlphabet>,
-    ) -> impl Iterator<Item = Self::OutputAlphabet> {
-        input.scan(Self::StateSpace::default(), |state, i| {
-            *state = Self::transition(state, &i);
-            Some(Self::output(state))
-        })
-    }
-}
+// The `Moore` trait itself lives in `crate::machine`, so that it can be used for other formats too.
 
 /*
-An identifying feature of a state machine is the number of states used in the computation. It looks at first glance that this one only has 18 states, but enums are sum types. This means that the size (cardinality) of the enum is actually the sum of cardinalities of each variant. `State::Comment` is a unit variant and so has a cardinality of 1. Error has a cardinality equal to the cardinality of `ErrorKind`, which is 2. `StringPart` and `Value` have a cardinality equal to `char`. Since `char` can be any valid unicode symbol and there are 149,813 of those as of [Unicode 15.1.0](https://www.unicode.org/versions/Unicode15.1.0/), the total number of states is
+An identifying feature of a state machine is the number of states used in the computation. It looks at first glance that this one only has 20 states, but enums are sum types. This means that the size (cardinality) of the enum is actually the sum of cardinalities of each variant. `State::Comment` is a unit variant and so has a cardinality of 1. Error has a cardinality equal to the cardinality of `ErrorKind`, which is 5, but only `OddDoubleQuotes` and `MissingWhitespace` are ever reached here, the rest being for the other parsers. `StringPart` and `Value` have a cardinality equal to `char`. Since `char` can be any valid unicode symbol and there are 149,813 of those as of [Unicode 15.1.0](https://www.unicode.org/versions/Unicode15.1.0/), the total number of states the machine can be in is
 
-> (18 - 3) + 2 + 149,813 + 149,813
-> = 299,643
+> (20 - 3) + 2 + 149,813 + 149,813
+> = 299,645
 
 */
 
+/// Where the machine is within a document, along with the `char` it has just read where that matters.
 #[derive(Debug, PartialEq, Hash, Clone, Copy, Default)]
-enum State {
+pub enum State {
     Comment,
     #[default]
     Default,
@@ -88,8 +60,10 @@ enum State {
     Finished,
     MayBeEscapedReturn,
     MayBeNull,
+    NotAnEscapedReturn,
     Null,
     NullEndOfLine,
+    NullStartComment,
     StartComment,
     StartString,
     StringPart(char),
@@ -102,14 +76,16 @@ enum State {
 
 This is my output alphabet. If you compare this whole implementation to the `State` implementation, you will see that this doesn't exist. This enum is an additional indirection to highlight exactly which part of the logic is controlled by the state machine. Each variant represents a transformation, which my WsvBuilder knows how to interpret. Remember, functions and data are symmetrical.
 
-You may ask why the output set is not something else, such as a WsvValue directly. Go back to the definition of a state machine. The Output set is defined as *finite*. WsvValue has a variant `Value` which contains a `String`. String is an infinite set, and since enums are sum types, that makes the WsvValue have cardinality ∞ + 1 = ∞. This enum's cardinality, however, is the sum of the cardinalities of ErrorKind, char and 9 (There are 11 variants minus AddError and PushChar). `char` is a unicode character and there are 149_813 of those as of [Unicode 15.1.0](https://www.unicode.org/versions/Unicode15.1.0/), so Transform has a cardinality of 2 + 9 + 149_813 = 149_824. That is not infinite, and so is a valid Output Set.
+You may ask why the output set is not something else, such as a WsvValue directly. Go back to the definition of a state machine. The Output set is defined as *finite*. WsvValue has a variant `Value` which contains a `String`. String is an infinite set, and since enums are sum types, that makes the WsvValue have cardinality ∞ + 1 = ∞. This enum's cardinality, however, is the sum of the cardinalities of ErrorKind twice, char and 9 (There are 12 variants minus AddError, AddErrorBefore and PushChar). `char` is a unicode character and there are 149_813 of those as of [Unicode 15.1.0](https://www.unicode.org/versions/Unicode15.1.0/), so Transform has a cardinality of 5 + 5 + 9 + 149_813 = 149_832. That is not infinite, and so is a valid Output Set.
 
 
 */
 
+/// What the `WsvBuilder` does to the row as the machine enters each state.
 #[derive(Debug, PartialEq, Clone, Copy, Hash)]
-enum Transform {
+pub enum Transform {
     AddError(ErrorKind),
+    AddErrorBefore(ErrorKind),
     AddNull,
     AddNullAndRow,
     AddRow,
@@ -122,9 +98,10 @@ enum Transform {
     PushQuote,
 }
 /*
-For this use case, I have no need to run arbitrary code between state transitions. I only want the complete list of outputs. Therefore, my machine does not need to hold any data. I could add a `state: State` attribute, and a function in the trait to interact with that attribute, then write a function which lets me encapsulate the logic of iterating through one transition at a time, but that is not useful for WSV here.
+For this use case, I have no need to run arbitrary code between state transitions. I only want the complete list of outputs. Therefore, my machine does not need to hold any data. I could add a `state: State` attribute, and a function in the trait to interact with that attribute, then write a function which lets me encapsulate the logic of iterating through one transition at a time, but that is not useful for WSV here. When it is useful, say for teaching, `crate::machine::Stepper` holds the state on the outside instead.
 */
-struct WsvMachine {}
+/// WSV as a Moore machine. Feed it every `char` of a document as `Some`, then `None` to finish.
+pub struct WsvMachine {}
 
 impl Moore for WsvMachine {
     type StateSpace = State;
@@ -144,12 +121,14 @@ impl Moore for WsvMachine {
             State::Value(c) => Transform::PushChar(*c),
             State::StringPart(c) => Transform::PushChar(*c),
             State::Error(kind) => Transform::AddError(*kind),
+            State::NotAnEscapedReturn => Transform::AddErrorBefore(ErrorKind::MissingWhitespace),
             State::MayBeNull => Transform::PushDash,
             State::EscapedReturn => Transform::PushNewline,
             State::EscapedDoubleQuote => Transform::PushQuote,
             State::EndOfValue => Transform::AddValue,
             State::StartComment => Transform::AddValue,
             State::Null => Transform::AddNull,
+            State::NullStartComment => Transform::AddNull,
             State::EndOfLine => Transform::AddRow,
             State::NullEndOfLine => Transform::AddNullAndRow,
             State::EndOfValueAndEndOfLine => Transform::AddValueAndRow,
@@ -167,6 +146,9 @@ impl Moore for WsvMachine {
             (State::StartComment, Some('\n')) => State::EndOfLine,
             (State::StartComment, None) => State::Finished,
             (State::StartComment, _) => State::Comment,
+            (State::NullStartComment, Some('\n')) => State::EndOfLine,
+            (State::NullStartComment, None) => State::Finished,
+            (State::NullStartComment, _) => State::Comment,
             (State::EndOfValueAndEndOfLine, None) => State::Finished,
             (State::EndOfValueAndEndOfLine, Some('\n')) => State::EndOfLine,
             (State::EndOfValueAndEndOfLine, Some('#')) => State::Comment,
@@ -204,7 +186,7 @@ impl Moore for WsvMachine {
             (State::EndOfValue, Some(c)) => State::Value(*c),
             (State::Null, None) => State::Finished,
             (State::Null, Some('\n')) => State::EndOfLine,
-            (State::Null, Some('#')) => State::EndOfLine,
+            (State::Null, Some('#')) => State::Comment,
             (State::Null, Some('-')) => State::MayBeNull,
             (State::Null, Some('\"')) => State::StartString,
             (State::Null, Some(c)) if c.is_whitespace() => State::Default,
@@ -213,6 +195,7 @@ impl Moore for WsvMachine {
             (State::MayBeNull, Some('\n')) => State::NullEndOfLine,
             (State::MayBeNull, Some(c)) if c.is_whitespace() => State::Null,
             (State::MayBeNull, Some('\"')) => State::Error(ErrorKind::MissingWhitespace),
+            (State::MayBeNull, Some('#')) => State::NullStartComment,
             (State::MayBeNull, Some(c)) => State::Value(*c),
             (State::Value(_), None) => State::EndOfValue,
             (State::Value(_), Some('\n')) => State::EndOfValueAndEndOfLine,
@@ -240,7 +223,8 @@ impl Moore for WsvMachine {
             (State::EscapeOrEndOfString, Some(c)) if c.is_whitespace() => State::EndOfValue,
             (State::EscapeOrEndOfString, _) => State::Error(ErrorKind::MissingWhitespace),
             (State::MayBeEscapedReturn, Some('\"')) => State::EscapedReturn,
-            (State::MayBeEscapedReturn, _) => State::Error(ErrorKind::MissingWhitespace),
+            (State::MayBeEscapedReturn, _) => State::NotAnEscapedReturn,
+            (State::NotAnEscapedReturn, _) => State::Finished,
             (State::StringPart(_), None) => State::Error(ErrorKind::OddDoubleQuotes),
             (State::StringPart(_), Some('\n')) => State::Error(ErrorKind::OddDoubleQuotes),
             (State::StringPart(_), Some('\"')) => State::EscapeOrEndOfString,
@@ -249,6 +233,101 @@ impl Moore for WsvMachine {
     }
 }
 
+/// One of every state for the diagrams. `StringPart` and `Value` hold the `char` they were
+/// entered on, but nothing leaves them any differently for it, so `'a'` stands in for all of
+/// them. Only the errors the machine can report are here, so that any other node drawn with
+/// no way into it is a real mistake.
+const STATES: [State; 21] = [
+    State::Comment,
+    State::Default,
+    State::EndOfLine,
+    State::EndOfValue,
+    State::EndOfValueAndEndOfLine,
+    State::Error(ErrorKind::OddDoubleQuotes),
+    State::Error(ErrorKind::MissingWhitespace),
+    State::EscapeOrEndOfString,
+    State::EscapedReturn,
+    State::EscapedDoubleQuote,
+    State::Finished,
+    State::MayBeEscapedReturn,
+    State::MayBeNull,
+    State::NotAnEscapedReturn,
+    State::Null,
+    State::NullEndOfLine,
+    State::NullStartComment,
+    State::StartComment,
+    State::StartString,
+    State::StringPart('a'),
+    State::Value('a'),
+];
+
+/// Where a state is in `STATES`, whatever `char` it holds, or `None` for an error the machine
+/// never reports. There is no wildcard, so neither a new state nor a new error kind will compile
+/// until it has been placed.
+const fn position(state: State) -> Option<usize> {
+    Some(match state {
+        State::Comment => 0,
+        State::Default => 1,
+        State::EndOfLine => 2,
+        State::EndOfValue => 3,
+        State::EndOfValueAndEndOfLine => 4,
+        State::Error(ErrorKind::OddDoubleQuotes) => 5,
+        State::Error(ErrorKind::MissingWhitespace) => 6,
+        State::Error(ErrorKind::Nom | ErrorKind::LimitExceeded | ErrorKind::InvalidUtf8) => {
+            return None
+        }
+        State::EscapeOrEndOfString => 7,
+        State::EscapedReturn => 8,
+        State::EscapedDoubleQuote => 9,
+        State::Finished => 10,
+        State::MayBeEscapedReturn => 11,
+        State::MayBeNull => 12,
+        State::NotAnEscapedReturn => 13,
+        State::Null => 14,
+        State::NullEndOfLine => 15,
+        State::NullStartComment => 16,
+        State::StartComment => 17,
+        State::StartString => 18,
+        State::StringPart(_) => 19,
+        State::Value(_) => 20,
+    })
+}
+
+// Every state in `STATES` is at its own place, so none is missing or listed twice.
+const _: () = {
+    let mut index = 0;
+    while index < STATES.len() {
+        assert!(matches!(position(STATES[index]), Some(place) if place == index));
+        index += 1;
+    }
+};
+
+/// Drawn with `crate::machine::Diagram`, one node for each variant of `State`.
+impl Enumerable for WsvMachine {
+    type State = State;
+    type Input = Option<char>;
+    type Output = Transform;
+    fn states() -> Vec<State> {
+        STATES.to_vec()
+    }
+    fn input_classes() -> Vec<(&'static str, Option<char>)> {
+        crate::mealy::INPUT_CLASSES.to_vec()
+    }
+    fn state_name(state: &State) -> String {
+        match state {
+            State::StringPart(_) => "StringPart".to_owned(),
+            State::Value(_) => "Value".to_owned(),
+            _ => format!("{state:?}"),
+        }
+    }
+    fn output_name(output: &Transform) -> String {
+        match output {
+            Transform::PushChar(_) => "PushChar".to_owned(),
+            _ => format!("{output:?}"),
+        }
+    }
+}
+
 /*
 The WsvBuilder is the data structure which gets incrementally modified by the transforms, and produces the final parser output. There is no difference between this Builder and the one in the Mealy variant. However, I have kept each implementation with their own definitions to simplify maintenance.
 
@@ -286,6 +365,10 @@ impl WsvBuilder {
                 self.col += 1;
                 self.err = Some(Error::new(kind, self.row, self.col, None));
             }
+            // the `/` after a string turned out to start a cell with no whitespace before it.
+            Transform::AddErrorBefore(kind) => {
+                self.err = Some(Error::new(kind, self.row, self.col, None));
+            }
             Transform::PushDash => {
                 self.col += 1;
                 self.buf.push('-');
@@ -316,43 +399,41 @@ impl WsvBuilder {
             }
             Transform::IncrementColumnNumber => self.col += 1,
             Transform::AddValueAndRow => {
-                self.col += 1;
-
                 self.out
                     .last_mut()
                     .expect("initialised with one")
                     .push(WsvValue::V(self.buf.clone()));
-                self.out.push(vec![]);
+                self.add_row();
 
                 self.buf.clear();
             }
             Transform::AddNullAndRow => {
-                self.col += 1;
-
                 self.out
                     .last_mut()
                     .expect("initialised with one")
                     .push(WsvValue::Null);
-                self.out.push(vec![]);
+                self.add_row();
 
                 self.buf.clear();
             }
-            Transform::AddRow => {
-                self.col += 1;
-
-                self.out.push(vec![])
-            }
+            Transform::AddRow => self.add_row(),
         }
     }
+    /// Errors on the next row are counted from its own first column.
+    fn add_row(&mut self) {
+        self.row += 1;
+        self.col = 0;
+        self.out.push(vec![]);
+    }
     fn finish(self) -> Result<Vec<Vec<WsvValue>>, Error> {
         match self.err {
-            Some(e) => Err(dbg!(e)),
+            Some(e) => Err(e),
             None => Ok(self.out),
         }
     }
     fn finish_row(mut self) -> Result<Vec<WsvValue>, Error> {
         match self.err {
-            Some(e) => Err(dbg!(e)),
+            Some(e) => Err(e),
             None => Ok(self.out.pop().unwrap()),
         }
     }
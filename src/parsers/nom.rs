@@ -1,25 +1,27 @@
 //! This implementation was heavily informed by the grammar I wrote for the pest parser.
 //!
-//! It is currently the top contender for fastest, but it still does not satisfy the
-//! requirement of clear error messages. If someone knows a way to extract row and column
-//! numbers, and even to differentiate between the two error states, I would love to know.
-//! Initial investigation suggests using nom-supreme. Any other ideas?
+//! It is currently the top contender for fastest. For a long time it could not say what went
+//! wrong or where, since nom's errors only say which combinator gave up. In the end the answer
+//! was not to ask nom at all: `line` never fails, it only stops, and where it stops, along with
+//! the `char` before it, is enough to tell the two kinds of error apart. See `to_error`.
 //!
 //! This is also the only implementation that required unit tests for me. This was my first
 //! use of the crate so I wanted confidence on the outcome of each parser. Since it now works
 //! and the crate passes all the standard unit tests, the specific ones could be deleted. I
-//! keep them just in case the above fixes can be made.
+//! keep them as a record of how each combinator behaves.
 
 pub(crate) use nom::{
     branch::alt,
     bytes::complete::{tag, take_till, take_while},
     character::complete::char,
-    combinator::{all_consuming, map, value as ifthen, verify},
+    combinator::{consumed, map, not, value as ifthen, verify},
     multi::{many0, separated_list0},
-    sequence::delimited,
+    sequence::{delimited, terminated},
     IResult,
 };
 
+use std::cell::RefCell;
+
 use crate::data_model::*;
 
 pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
@@ -29,10 +31,21 @@ pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
 /// Here, I have built a nom parser to handle new lines as well, which is what is being tested in
 /// the micro benchmark.
 pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
-    match all_consuming(wsv)(i) {
-        Ok((_, o)) => Ok(o),
-        Err(e) => Err(Error::new(ErrorKind::Nom, 0, 0, Some(e.to_string().into()))),
+    let (rest, rows) = wsv(i).expect("every parser in `line` can match nothing");
+    if rest.is_empty() {
+        return Ok(rows);
     }
+    let before = &i[..i.len() - rest.len()];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let line_end = rest
+        .find('\n')
+        .map_or(i.len(), |index| before.len() + index);
+    let row_index = before.matches('\n').count();
+    Err(to_error(
+        row_index,
+        &i[line_start..line_end],
+        &rest[..line_end - before.len()],
+    ))
 }
 
 fn wsv(i: &str) -> IResult<&str, Vec<Vec<WsvValue>>> {
@@ -41,19 +54,24 @@ fn wsv(i: &str) -> IResult<&str, Vec<Vec<WsvValue>>> {
 
 // we assume that line has no `\n`.
 pub fn parse_line((row_index, input): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
-    let row = row_index + 1;
-    match all_consuming(line)(input) {
-        Ok((_, o)) => Ok(o),
-        Err(e) => Err(Error::new(
-            ErrorKind::Nom,
-            row,
-            0,
-            Some(e.to_string().into()),
-        )),
+    match line(input).expect("every parser in `line` can match nothing") {
+        ("", o) => Ok(o),
+        (rest, _) => Err(to_error(row_index, input, rest)),
     }
 }
 
-#[tracing::instrument]
+/// `line` never fails, it just stops, and `rest` is whatever it could not match. It stops either
+/// at a cell with no whitespace before it, or, since a value cannot start with a quote, at a
+/// string which is never closed. The missing quote belongs at the end of the line.
+fn to_error(row_index: usize, input: &str, rest: &str) -> Error {
+    let before = &input[..input.len() - rest.len()];
+    let (kind, col) = match before.chars().next_back() {
+        Some(c) if !c.is_whitespace() => (ErrorKind::MissingWhitespace, before.chars().count() + 1),
+        _ => (ErrorKind::OddDoubleQuotes, input.chars().count() + 1),
+    };
+    Error::new(kind, row_index + 1, col, None)
+}
+
 fn line(i: &str) -> IResult<&str, Vec<WsvValue>> {
     let (i, _) = ws0(i)?;
     let (i, o) = separated_list0(ws1, alt((nul, string, value)))(i)?;
@@ -65,6 +83,51 @@ fn line(i: &str) -> IResult<&str, Vec<WsvValue>> {
     Ok((i, o))
 }
 
+/// Which parser matched what, in order, for `crate::trace`.
+type Notes<'a> = RefCell<Vec<(&'static str, &'a str)>>;
+
+/// `line`, noting which parser matched each piece of it as it goes. It is made of the same
+/// parsers in the same order, so that `line` itself pays nothing for the trace, and
+/// `rules_agree_with_line` checks that the two stay in step.
+fn noted_line<'a>(i: &'a str, notes: &Notes<'a>) -> IResult<&'a str, Vec<WsvValue>> {
+    let (i, _) = noted("ws0", notes, ws0)(i)?;
+    let cell = alt((
+        noted("nul", notes, nul),
+        noted("string", notes, string),
+        noted("value", notes, value),
+    ));
+    let (i, o) = separated_list0(noted("ws1", notes, ws1), cell)(i)?;
+    let (i, _) = noted("ws0", notes, ws0)(i)?;
+    let (i, _) = match noted("comment", notes, comment)(i) {
+        Ok((i, o)) => (i, o),
+        Err(_) => (i, ""),
+    };
+    Ok((i, o))
+}
+
+/// `parser`, noting what it matched whenever it matches. A match which starts at or before an
+/// earlier one means nom went back over that part of the line, as `separated_list0` does when a
+/// separator has no cell after it, so the earlier one never counted and is dropped.
+fn noted<'a: 'r, 'r, O: 'r>(
+    rule: &'static str,
+    notes: &'r Notes<'a>,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O> + 'r,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> + 'r {
+    map(consumed(parser), move |(matched, o)| {
+        let mut notes = notes.borrow_mut();
+        while notes
+            .last()
+            .is_some_and(|(_, earlier)| earlier.as_ptr() >= matched.as_ptr())
+        {
+            notes.pop();
+        }
+        if !matched.is_empty() {
+            notes.push((rule, matched));
+        }
+        o
+    })
+}
+
 fn ws0(i: &str) -> IResult<&str, &str> {
     take_while(|c: char| c.is_whitespace() && c != '\n')(i)
 }
@@ -76,11 +139,11 @@ fn ws1(i: &str) -> IResult<&str, &str> {
     )(i)
 }
 
+/// A dash is only a null if the value ends there, so `--` and `-1` are values.
 fn nul(i: &str) -> IResult<&str, WsvValue> {
-    ifthen(WsvValue::Null, tag("-"))(i)
+    ifthen(WsvValue::Null, terminated(tag("-"), not(value)))(i)
 }
 
-#[tracing::instrument]
 fn string(i: &str) -> IResult<&str, WsvValue> {
     map(
         delimited(
@@ -121,6 +184,14 @@ fn comment(i: &str) -> IResult<&str, &str> {
     take_till(|c| c == '\n')(i)
 }
 
+/// Which parser matched each piece of a line, as `line` parsed it, for `crate::trace`. Whatever
+/// is left over at the end is where `line` stopped.
+pub(crate) fn rules(i: &str) -> Vec<(&'static str, &str)> {
+    let notes = RefCell::new(vec![]);
+    let _ = noted_line(i, &notes);
+    notes.into_inner()
+}
+
 #[cfg(test)]
 mod nom_tests {
     use super::*;
@@ -274,6 +345,27 @@ mod nom_tests {
             ))
         );
     }
+
+    /// Whatever nom backtracked over has to be gone from `rules`, or the trace would show a
+    /// separator which was never used. So on every line of the conformance suite, the pieces it
+    /// keeps add up to what `line` matched, and the cells among them are the values it gave back.
+    #[test]
+    fn rules_agree_with_line() {
+        for case in crate::conformance::load("./tests/conformance").unwrap() {
+            for text in case.input.split('\n') {
+                let (rest, values) = line(text).unwrap();
+                let rules = rules(text);
+                let matched = rules.iter().map(|(_, piece)| *piece).collect::<String>();
+                assert_eq!(matched, text[..text.len() - rest.len()], "{}", case.name);
+                let cells = rules
+                    .iter()
+                    .filter(|(name, _)| matches!(*name, "nul" | "string" | "value"))
+                    .map(|(_, piece)| WsvValue::convert(piece))
+                    .collect::<Vec<_>>();
+                assert_eq!(cells, values, "{}", case.name);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,109 @@
+//! An LL(k) parser, generated by parol from the grammar in parol/wsv.par.
+//!
+//! Parol sits between the two camps. Like LALRPOP it builds its parse tables in `build.rs` and
+//! never backtracks, but like the PEGs it parses top down, looking ahead as many tokens as it
+//! needs to choose an alternative. The grammar only needs two, to tell whitespace between cells
+//! from whitespace at the end of a line.
+//!
+//! It also generates a typed AST, one struct per rule, and a trait with a method per rule. The
+//! user type in parol/grammar.rs implements that trait, and those methods are called as each
+//! rule is parsed, so the rows are built up as the parse goes.
+//!
+//! Parol's scanner is generated from the grammar's regexes, so a string which is never closed
+//! needs a token of its own. The grammar accepts it as a cell, and the user type remembers the
+//! first one, because the error belongs at the end of the line, where the closing quote is
+//! missing, rather than at the token the parser stopped on.
+
+use parol_runtime::{ParolError, ParserError};
+
+use crate::data_model::*;
+use grammar::WsvGrammar;
+
+pub(crate) mod grammar;
+#[allow(clippy::all, unused)]
+mod grammar_trait {
+    include!(concat!(env!("OUT_DIR"), "/grammar_trait.rs"));
+}
+#[allow(clippy::all, unused)]
+mod parser {
+    include!(concat!(env!("OUT_DIR"), "/parser.rs"));
+}
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    let mut grammar = WsvGrammar::default();
+    let result = parser::parse(i, "", &mut grammar);
+    match (grammar.unclosed, result) {
+        (Some(end), _) => Err(to_error(i, 0, ErrorKind::OddDoubleQuotes, end, None)),
+        (None, Err(e)) => Err(syntax_error(i, 0, e)),
+        (None, Ok(_)) => Ok(grammar.rows),
+    }
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    let mut grammar = WsvGrammar::default();
+    let result = parser::parse(line, "", &mut grammar);
+    match (grammar.unclosed, result) {
+        (Some(end), _) => Err(to_error(
+            line,
+            row_index,
+            ErrorKind::OddDoubleQuotes,
+            end,
+            None,
+        )),
+        (None, Err(e)) => Err(syntax_error(line, row_index, e)),
+        (None, Ok(_)) => Ok(grammar.rows.remove(0)),
+    }
+}
+
+/// Any token the parser did not expect can only be a cell which should have had whitespace before
+/// it. Parol's own error is kept as the source.
+fn syntax_error(input: &str, row_index: usize, e: ParolError) -> Error {
+    let offset = match &e {
+        ParolError::ParserError(ParserError::SyntaxErrors { entries }) => {
+            entries
+                .first()
+                .map(|entry| match entry.unexpected_tokens.first() {
+                    Some(unexpected) => unexpected.token.start,
+                    None => entry.error_location.start,
+                })
+        }
+        ParolError::ParserError(ParserError::UnprocessedInput { last_token, .. }) => {
+            Some(last_token.end)
+        }
+        _ => None,
+    };
+    let offset = offset.map_or(0, |offset| offset as usize);
+    to_error(
+        input,
+        row_index,
+        ErrorKind::MissingWhitespace,
+        offset,
+        Some(e),
+    )
+}
+
+/// Parol counts in bytes from the start of the input, so this turns an offset into a row and a
+/// column counted in `char`s.
+fn to_error(
+    input: &str,
+    row_index: usize,
+    kind: ErrorKind,
+    offset: usize,
+    source: Option<ParolError>,
+) -> Error {
+    let before = &input[..offset];
+    let row = row_index + before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+    Error::new(kind, row, col, source.map(|e| e.to_string().into()))
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
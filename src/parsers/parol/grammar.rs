@@ -0,0 +1,46 @@
+//! The user type for the generated parser. Its actions turn each line into a row as soon as the
+//! line has been parsed.
+
+use std::marker::PhantomData;
+
+use parol_runtime::Result;
+
+use super::grammar_trait::{Cell, Line, Unclosed, WsvGrammarTrait};
+use crate::data_model::{unescape, WsvValue};
+
+#[derive(Debug, Default)]
+pub(crate) struct WsvGrammar<'t> {
+    pub rows: Vec<Vec<WsvValue>>,
+    /// The byte offset where the first string which was never closed should have been closed.
+    pub unclosed: Option<usize>,
+    phantom: PhantomData<&'t str>,
+}
+
+impl<'t> WsvGrammarTrait<'t> for WsvGrammar<'t> {
+    fn line(&mut self, arg: &Line<'t>) -> Result<()> {
+        let row = match &arg.line_opt0 {
+            Some(cells) => std::iter::once(&cells.cells.cell)
+                .chain(cells.cells.cells_list.iter().map(|item| &item.cell))
+                .map(to_value)
+                .collect(),
+            None => vec![],
+        };
+        self.rows.push(row);
+        Ok(())
+    }
+
+    fn unclosed(&mut self, arg: &Unclosed<'t>) -> Result<()> {
+        self.unclosed
+            .get_or_insert(arg.unclosed.location.end as usize);
+        Ok(())
+    }
+}
+
+fn to_value(cell: &Cell) -> WsvValue {
+    match cell {
+        Cell::Null(_) => WsvValue::Null,
+        Cell::Value(value) => WsvValue::V(value.value.value.text().to_owned()),
+        Cell::Quoted(quoted) => WsvValue::convert(quoted.quoted.quoted.text()),
+        Cell::Unclosed(unclosed) => WsvValue::V(unescape(&unclosed.unclosed.unclosed.text()[1..])),
+    }
+}
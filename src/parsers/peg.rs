@@ -0,0 +1,66 @@
+//! This is the wsv.pest grammar, rewritten as a rust-peg grammar.
+//!
+//! Both crates are PEG parser generators, so the rules translate almost one for one. The big
+//! difference is where the grammar lives. Pest reads a separate file and hands you a tree of
+//! `Pair`s to walk, whereas peg's grammar is a macro in the Rust source and every rule returns a
+//! Rust value directly, so there is no tree to walk at all.
+//!
+//! Errors are reported at the furthest position any rule reached, along with the set of things
+//! which would have been accepted there. Labelling the closing quote with `expected!` is enough to
+//! tell the two kinds of error apart.
+
+use crate::data_model::*;
+use peg::error::ParseError;
+use peg::str::LineCol;
+
+peg::parser! {
+    grammar wsv() for str {
+        rule whitespace() = quiet!{ [c if c.is_whitespace() && c != '\n'] } / expected!("whitespace")
+        rule new_line() -> &'static str = "\"/\"" { "\n" }
+        rule double_quote() -> &'static str = "\"\"" { "\"" }
+        rule string_part() -> &'input str = $([^ '\n' | '"']+)
+
+        rule null() -> WsvValue = "-" !value() { WsvValue::Null }
+        rule value() -> WsvValue
+            = v:$([c if !(c == '\n' || c == '"' || c == '#' || c.is_whitespace())]+) { WsvValue::V(v.to_owned()) }
+        rule string() -> WsvValue
+            = "\"" parts:(double_quote() / new_line() / string_part())* ("\"" / expected!("closing quote"))
+            { WsvValue::V(parts.concat()) }
+        rule comment() = "#" [^ '\n']*
+
+        rule item() -> WsvValue = null() / string() / value()
+
+        pub rule line() -> Vec<WsvValue>
+            = first:item()? rest:(whitespace()+ v:item() { v })* whitespace()* comment()?
+            { first.into_iter().chain(rest).collect() }
+        pub rule wsv() -> Vec<Vec<WsvValue>> = line() ** "\n"
+    }
+}
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    wsv::wsv(i).map_err(|e| to_error(0, e))
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    wsv::line(line).map_err(|e| to_error(row_index, e))
+}
+
+/// peg already counts lines and `char` columns from 1, so only the kind needs working out.
+fn to_error(row_index: usize, e: ParseError<LineCol>) -> Error {
+    let kind = if e.expected.tokens().any(|token| token == "closing quote") {
+        ErrorKind::OddDoubleQuotes
+    } else {
+        ErrorKind::MissingWhitespace
+    };
+    Error::new(kind, row_index + e.location.line, e.location.column, None)
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
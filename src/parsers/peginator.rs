@@ -0,0 +1,100 @@
+//! This is the wsv.pest grammar, rewritten in peginator's EBNF dialect in wsv.ebnf.
+//!
+//! Peginator sits somewhere between pest and peg. The grammar lives in its own file like pest's,
+//! but it is compiled by `build.rs` into a typed AST rather than a generic tree of pairs, with one
+//! struct or enum per rule. All that is left to do here is flatten that AST into `WsvValue`s.
+//!
+//! Two rules are written in Rust with `@extern`. Peginator only knows about ASCII whitespace, so
+//! `Whitespace` uses `char::is_whitespace` instead. `ClosingQuote` is only a separate rule so that
+//! its failure can be recognised: peginator reports the last thing it tried at the furthest
+//! position it reached, and if that was a closing quote, the quotes were odd.
+
+use crate::data_model::*;
+use grammar::*;
+use peginator::{ParseError, ParseErrorSpecifics, PegParser};
+
+/// The generated code trips a couple of lints of its own, which are not ours to fix.
+#[allow(unused_assignments, clippy::useless_conversion)]
+mod grammar {
+    include!(concat!(env!("OUT_DIR"), "/wsv_peginator.rs"));
+}
+
+const CLOSING_QUOTE: &str = "closing quote";
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    match Document::parse(i) {
+        Ok(document) => Ok(document
+            .rows
+            .into_iter()
+            .map(|row| convert(row.cells))
+            .collect()),
+        Err(e) => Err(to_error(i, 0, e)),
+    }
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    match Line::parse(line) {
+        Ok(line) => Ok(convert(line.cells)),
+        Err(e) => Err(to_error(line, row_index, e)),
+    }
+}
+
+fn convert(cells: Vec<Cell>) -> Vec<WsvValue> {
+    cells
+        .into_iter()
+        .map(|cell| match cell {
+            Cell::Null(_) => WsvValue::Null,
+            Cell::Value(value) => WsvValue::V(value),
+            Cell::Quoted(quoted) => WsvValue::V(
+                quoted
+                    .parts
+                    .iter()
+                    .map(|part| match part {
+                        QuotedPart::DoubleQuote(_) => "\"",
+                        QuotedPart::NewLine(_) => "\n",
+                        QuotedPart::StringPart(part) => part,
+                    })
+                    .collect(),
+            ),
+        })
+        .collect()
+}
+
+/// The error position is a byte offset from the start of the input.
+fn to_error(input: &str, row_index: usize, e: ParseError) -> Error {
+    let before = &input[..e.position];
+    let row = row_index + before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+    let kind = match e.specifics {
+        ParseErrorSpecifics::ExternRuleFailed {
+            error_string: CLOSING_QUOTE,
+        } => ErrorKind::OddDoubleQuotes,
+        _ => ErrorKind::MissingWhitespace,
+    };
+    Error::new(kind, row, col, None)
+}
+
+pub(crate) fn whitespace(s: &str) -> Result<(char, usize), &'static str> {
+    match s.chars().next() {
+        Some(c) if c.is_whitespace() && c != '\n' => Ok((c, c.len_utf8())),
+        _ => Err("whitespace"),
+    }
+}
+
+pub(crate) fn closing_quote(s: &str) -> Result<(char, usize), &'static str> {
+    match s.starts_with('"') {
+        true => Ok(('"', 1)),
+        false => Err(CLOSING_QUOTE),
+    }
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
@@ -7,6 +7,7 @@
 //! and was one of the fastest to implement from ignorance.
 //!
 //! There is no `fn parse` here, since that would require a separate `.pest` grammar, which defeats the point of a grammar for me.
+//! `parse_line` only exists so that pest can take part in the registry and the benchmarks.
 
 use crate::data_model::*;
 use pest::error::Error as pestError;
@@ -49,6 +50,36 @@ pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
         .collect())
 }
 
+/// The grammar only knows about whole documents, so a line is parsed as a document with one row
+/// in it.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    match parse_strict(line) {
+        Ok(mut rows) => Ok(rows.pop().unwrap_or_default()),
+        Err(mut e) => {
+            e.row += row_index;
+            Err(e)
+        }
+    }
+}
+
+/// The rules which matched each `char` of a line, outermost first, for `crate::trace`. The line
+/// is matched as far as it can be, and whatever is left over gets no rules at all.
+pub(crate) fn rules(line: &str) -> Vec<Vec<String>> {
+    let offsets = line.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+    let mut rules = vec![vec![]; offsets.len()];
+    if let Ok(pairs) = WsvParser::parse(Rule::Line, line) {
+        for pair in pairs.flatten() {
+            let span = pair.as_span();
+            for (c, offset) in offsets.iter().enumerate() {
+                if (span.start()..span.end()).contains(offset) {
+                    rules[c].push(format!("{:?}", pair.as_rule()));
+                }
+            }
+        }
+    }
+    rules
+}
+
 impl From<pestError<Rule>> for Error {
     fn from(value: pestError<Rule>) -> Self {
         let (row, col) = match value.line_col {
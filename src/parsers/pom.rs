@@ -0,0 +1,138 @@
+//! This is the nom grammar, ported to pom.
+//!
+//! Pom builds parsers out of operator overloads rather than function calls, so `a + b` is a
+//! sequence, `a * b` and `a - b` keep only one side of it, `a | b` is a choice and `-a` looks
+//! ahead. It runs over a slice of tokens, here `char`s, so error positions are already columns.
+//!
+//! What it does not have is any way to commit to an alternative once repetition is involved, as
+//! `repeat` and `list` stop at the first failure of any kind. So a string which is never closed
+//! is still parsed as a cell, one which remembers where it should have been closed, and the error
+//! is raised after the line has been parsed.
+
+use pom::parser::{empty, end, is_a, list, none_of, seq, sym, Parser};
+
+use crate::data_model::*;
+
+/// A cell, or the position where a string should have been closed.
+type Cell = Result<WsvValue, usize>;
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    let input = i.chars().collect::<Vec<_>>();
+    let line = line() - end();
+    input
+        .split(|c| *c == '\n')
+        .enumerate()
+        .map(|(row_index, chars)| parse_chars(&line, row_index, chars))
+        .collect()
+}
+
+/// Unlike nom, newlines are split on first, since pom stops at the first row it cannot parse
+/// without having checked the strings left open on the rows before.
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    let input = i.chars().collect::<Vec<_>>();
+    let line = line() - end();
+    input
+        .split(|c| *c == '\n')
+        .enumerate()
+        .map(|(row_index, chars)| parse_chars(&line, row_index, chars))
+        .collect()
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, input): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    let input = input.chars().collect::<Vec<_>>();
+    let line = line() - end();
+    parse_chars(&line, row_index, &input)
+}
+
+/// The parser is built by the caller, so that a whole document only builds it once.
+fn parse_chars<'a>(
+    line: &Parser<'a, char, Vec<Cell>>,
+    row_index: usize,
+    input: &'a [char],
+) -> Result<Vec<WsvValue>, Error> {
+    let row = line
+        .parse(input)
+        .map_err(|e| syntax_error(row_index, input.len(), e))?;
+    row.into_iter()
+        .collect::<Result<_, _>>()
+        .map_err(|at| to_error(row_index, ErrorKind::OddDoubleQuotes, at))
+}
+
+/// Anything left over after the last cell means some whitespace was missing. Pom's own error is
+/// kept as the source.
+fn syntax_error(row_index: usize, len: usize, e: pom::Error) -> Error {
+    let at = match e {
+        pom::Error::Incomplete => len,
+        pom::Error::Mismatch { position, .. }
+        | pom::Error::Conversion { position, .. }
+        | pom::Error::Expect { position, .. }
+        | pom::Error::Custom { position, .. } => position,
+    };
+    let mut error = to_error(row_index, ErrorKind::MissingWhitespace, at);
+    error.source = Some(e.to_string().into());
+    error
+}
+
+/// Positions are indexes into the `char`s of one line, so they are columns already.
+fn to_error(row_index: usize, kind: ErrorKind, at: usize) -> Error {
+    Error::new(kind, row_index + 1, at + 1, None)
+}
+
+fn is_whitespace(c: char) -> bool {
+    c.is_whitespace() && c != '\n'
+}
+
+/// Whitespace only separates two cells if another cell comes after it.
+fn line<'a>() -> Parser<'a, char, Vec<Cell>> {
+    let separator = is_a(is_whitespace).repeat(1..) - -none_of("#\n");
+    is_a(is_whitespace).repeat(0..) * list(nul() | string() | value(), separator)
+        - is_a(is_whitespace).repeat(0..)
+        - comment().opt()
+}
+
+fn nul<'a>() -> Parser<'a, char, Cell> {
+    (sym('-') - !value()).map(|_| Ok(WsvValue::Null))
+}
+
+fn string<'a>() -> Parser<'a, char, Cell> {
+    let part =
+        none_of("\"\n") | seq(&['"', '"']).map(|_| '"') | seq(&['"', '/', '"']).map(|_| '\n');
+    let closed = sym('"').map(|_| None) | empty().pos().map(Some);
+    (sym('"') * part.repeat(0..) + closed).map(|(s, unclosed)| match unclosed {
+        None => Ok(WsvValue::V(s.into_iter().collect())),
+        Some(at) => Err(at),
+    })
+}
+
+fn value<'a>() -> Parser<'a, char, Cell> {
+    is_a(|c: char| !(c == '"' || c == '#' || c.is_whitespace()))
+        .repeat(1..)
+        .map(|s| Ok(WsvValue::V(s.into_iter().collect())))
+}
+
+fn comment<'a>() -> Parser<'a, char, ()> {
+    (sym('#') * none_of("\n").repeat(0..)).discard()
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
+
+#[cfg(test)]
+mod pom_tests {
+    use super::*;
+
+    /// A string left open on one row comes before a missing whitespace on the next.
+    #[test]
+    fn reports_the_first_error_in_the_document() {
+        for (input, col) in [("\"x\nb\"c\"", 3), ("a \"b\nc\"d\"", 5)] {
+            let error = parse_strict(input).unwrap_err();
+            assert_eq!(
+                (error.kind, error.row, error.col),
+                (ErrorKind::OddDoubleQuotes, 1, col)
+            );
+        }
+    }
+}
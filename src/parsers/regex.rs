@@ -1,39 +1,73 @@
-use crate::data_model::Error;
-use crate::ErrorKind;
-use crate::WsvValue;
+//! One regex which splits a line into tokens, and a loop which checks they are in a sensible order.
+//!
+//! I first tried to do the whole line with a single regex, but a regex can only tell you that a
+//! line does not match, not where or why. Every character can start exactly one kind of token,
+//! so matching the regex below over and over walks the whole line with no gaps, and the place it
+//! goes wrong is just the start of the first token which should not be there.
+//!
+//! The regex crate prefers whatever its first choice at each step is, rather than the longest
+//! match, so a string takes every escape it can before its closing quote is even considered.
+//! That way `"""` is an escaped quote with no end, not an empty string followed by a stray quote.
+//! Nulls are matched as values, otherwise `-a` would be a null followed by a value.
+
+use std::sync::LazyLock;
+
 use regex::Regex;
 
-fn check() -> Regex {
-    Regex::new(
-        r##"^(-|"(?:""|"/"|[^\n"]+)*"|[^\s"#]+)?(?:[\s--\n]+(-|"(?:""|"/"|[^\n"]+)*"|[^\s"#]+))*[\s--\n]*(#.*$)?$"##
-    ).unwrap()
-}
+use crate::data_model::*;
 
-fn data() -> Regex {
-    Regex::new(r##"\s*#.*$|^\s*(?<first>-|"(?:""|"/"|[^\n"]+)*"|[^\s"#]+)|\s+(?<rest>-|"(?:""|"/"|[^\n"]+)*"|[^\s"#]+)"##).unwrap()
-}
+/// Compiling the regex takes far longer than running it on a line, so it is only done once.
+static TOKEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r##"(?<whitespace>[\s--\n]+)|(?<comment>#.*)|(?<string>"(?:""|"/"|[^\n"])*(?<closed>")?)|[^\s"#]+"##,
+    )
+    .unwrap()
+});
 
 pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
-    i.split("n").enumerate().map(parse_line).collect()
+    i.split('\n').enumerate().map(parse_line).collect()
 }
 
 pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
-    i.split("n").enumerate().map(parse_line).collect()
+    i.split('\n').enumerate().map(parse_line).collect()
 }
 
-pub fn parse_line((_line_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
-    dbg!(check().captures(line));
-
-    if check().is_match(line) {
-        Ok(data()
-            .captures_iter(line)
-            .enumerate()
-            .map(|(i, c)| dbg!(c).get(1 + i.min(1)).unwrap())
-            .map(|m| WsvValue::convert(m.as_str()))
-            .collect())
-    } else {
-        Err(Error::new(ErrorKind::OddDoubleQuotes, 0, 1, None))
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    let row = row_index + 1;
+    let col = |byte: usize| line[..byte].chars().count() + 1;
+
+    let mut values = vec![];
+    let mut separated = true;
+    for token in TOKEN.captures_iter(line) {
+        if token.name("whitespace").is_some() {
+            separated = true;
+            continue;
+        }
+        if token.name("comment").is_some() {
+            break;
+        }
+        let m = token.get(0).expect("every match has group 0");
+        if !separated {
+            return Err(Error::new(
+                ErrorKind::MissingWhitespace,
+                row,
+                col(m.start()),
+                None,
+            ));
+        }
+        if token.name("string").is_some() && token.name("closed").is_none() {
+            return Err(Error::new(
+                ErrorKind::OddDoubleQuotes,
+                row,
+                col(m.end()),
+                None,
+            ));
+        }
+        values.push(WsvValue::convert(m.as_str()));
+        separated = false;
     }
+    Ok(values)
 }
 
 #[cfg(test)]
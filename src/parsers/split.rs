@@ -8,7 +8,6 @@
 
 use crate::data_model::*;
 use itertools::{Itertools, Position};
-use tracing::debug;
 
 pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
     i.split('\n').enumerate().map(parse_line).collect()
@@ -39,12 +38,19 @@ pub fn parse_line((line_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Er
             return parse_line_without_comments((line_number, &line_without_comment));
         }
     }
-    Err(Error::new(
-        ErrorKind::OddDoubleQuotes,
-        line_number,
-        line_without_comment.len() + 1,
-        None,
-    ))
+    // The quotes never pair up, so the last string is never closed, and everything from its
+    // opening quote on is inside it. A missing whitespace before that still comes first, so the
+    // line is checked again with the string closed where it should have been.
+    let closed = format!("{line_without_comment}\"");
+    match parse_line_without_comments((line_number, &closed)) {
+        Err(e) if e.kind == ErrorKind::MissingWhitespace => Err(e),
+        _ => Err(Error::new(
+            ErrorKind::OddDoubleQuotes,
+            line_number,
+            line_without_comment.chars().count() + 1,
+            None,
+        )),
+    }
 }
 
 fn parse_line_without_comments((line_number, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
@@ -59,7 +65,7 @@ fn parse_line_without_comments((line_number, line): (usize, &str)) -> Result<Vec
             (Position::Only, _) => result.append(&mut process_part(part).0),
             (Position::First, _) => {
                 let (mut these_parts, _, leading_ws) = process_part(part);
-                col += part.len() + 1;
+                col += part.chars().count() + 1;
                 if !leading_ws && !part.is_empty() {
                     return Err(Error::new(
                         ErrorKind::MissingWhitespace,
@@ -87,31 +93,35 @@ fn parse_line_without_comments((line_number, line): (usize, &str)) -> Result<Vec
                 }
             }
             (Position::Middle, true) => {
-                col += part.len() + 1; // + 1 for the missing quote
+                col += part.chars().count() + 1; // + 1 for the missing quote
                 string.push_str(part);
             }
             (Position::Middle, false) => match identify_string_part(part) {
-                Decision::SpecialCharacter(ch) => string.push(ch),
+                Decision::SpecialCharacter(ch) => {
+                    col += part.chars().count() + 1;
+                    string.push(ch);
+                }
                 Decision::EndOfString => {
                     result.push(WsvValue::new(&string));
                     string.clear();
                     let (mut these_parts, trailing_ws, leading_ws) = process_part(part);
-                    if !leading_ws {
+                    // the whitespace after the closing quote comes first, so it is checked first.
+                    if !trailing_ws {
                         return Err(Error::new(
                             ErrorKind::MissingWhitespace,
                             line_number,
-                            col + part.len() + 1,
+                            col + 1,
                             None,
                         ));
-                    } else if !trailing_ws {
+                    } else if !leading_ws {
                         return Err(Error::new(
                             ErrorKind::MissingWhitespace,
                             line_number,
-                            col + 1,
+                            col + part.chars().count() + 1,
                             None,
                         ));
                     } else {
-                        col += part.len() + 1;
+                        col += part.chars().count() + 1;
                         result.append(&mut these_parts);
                     }
                 }
@@ -141,7 +151,8 @@ fn process_part(main_part: &str) -> (Vec<WsvValue>, bool, bool) {
 }
 
 fn identify_string_part(string_part: &str) -> Decision {
-    debug!(string_part);
+    #[cfg(feature = "tracing")]
+    tracing::trace!(string_part);
     if string_part == "/" {
         Decision::SpecialCharacter('\n')
     } else if string_part.is_empty() {
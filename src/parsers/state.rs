@@ -54,6 +54,15 @@ impl Data {
             State::Value(c) => self.buf.push(*c),
             State::StringPart(c) => self.buf.push(*c),
             State::Error(kind) => self.err = Some(Error::new(*kind, self.row, self.col, None)),
+            State::NotAnEscapedReturn => {
+                let col = self.col - 1;
+                self.err = Some(Error::new(
+                    ErrorKind::MissingWhitespace,
+                    self.row,
+                    col,
+                    None,
+                ))
+            }
 
             State::MayBeNull => self.buf.push('-'),
             State::EscapedReturn => self.buf.push('\n'),
@@ -66,7 +75,7 @@ impl Data {
                 self.out.push(WsvValue::V(self.buf.clone()));
                 self.buf.clear();
             }
-            State::Null => {
+            State::Null | State::NullAndComment => {
                 self.out.push(WsvValue::Null);
                 self.buf.clear();
             }
@@ -88,12 +97,16 @@ enum State {
     Finished,
     MayBeNull,
     Null,
+    NullAndComment,
     Value(char),
     EndOfValue,
     Error(ErrorKind),
     StartString,
     EscapeOrEndOfString,
     MayBeEscapedReturn,
+    /// The `/` after a string did not start a `"/"` after all, so it is a cell with no whitespace
+    /// before it. That is only known one `char` later, so the error goes back to the `/`.
+    NotAnEscapedReturn,
     EscapedReturn,
     EscapedDoubleQuote,
     StringPart(char),
@@ -104,7 +117,9 @@ impl State {
         match (self, event) {
             (State::Finished, _) => State::Finished,
             (State::Comment, _) => State::Finished,
+            (State::NullAndComment, _) => State::Finished,
             (State::Error(_), _) => State::Finished,
+            (State::NotAnEscapedReturn, _) => State::Finished,
 
             (State::Default, None) => State::Finished,
             (State::Default, Some('#')) => State::Finished,
@@ -130,6 +145,7 @@ impl State {
             (State::MayBeNull, None) => State::Null,
             (State::MayBeNull, Some(c)) if c.is_whitespace() => State::Null,
             (State::MayBeNull, Some('\"')) => State::Error(ErrorKind::MissingWhitespace),
+            (State::MayBeNull, Some('#')) => State::NullAndComment,
             (State::MayBeNull, Some(c)) => State::Value(c),
 
             (State::Value(_), None) => State::EndOfValue,
@@ -158,7 +174,7 @@ impl State {
             (State::EscapeOrEndOfString, _) => State::Error(ErrorKind::MissingWhitespace),
 
             (State::MayBeEscapedReturn, Some('\"')) => State::EscapedReturn,
-            (State::MayBeEscapedReturn, _) => State::Error(ErrorKind::MissingWhitespace),
+            (State::MayBeEscapedReturn, _) => State::NotAnEscapedReturn,
 
             (State::StringPart(_), None) => State::Error(ErrorKind::OddDoubleQuotes),
             (State::StringPart(_), Some('\"')) => State::EscapeOrEndOfString,
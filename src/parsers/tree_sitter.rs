@@ -0,0 +1,94 @@
+//! Walks the syntax tree from the tree-sitter grammar in tree-sitter-wsv.
+//!
+//! Tree-sitter is built for editors. It parses a file incrementally as it changes, and never gives
+//! up on it, so there is always a tree to highlight, errors and all. That is why the grammar is so
+//! forgiving: a row is just a run of cells, whitespace and comments in any order, and a string
+//! with no closing quote is a node of its own. So the two rules of WSV are checked here, on the
+//! way through the tree, in the same way as the logos lexer does it.
+
+use std::cell::RefCell;
+
+use tree_sitter::Node;
+
+use crate::data_model::*;
+
+thread_local! {
+    /// A parser is cheap to reuse but not to make, as it allocates its parse stack up front.
+    static PARSER: RefCell<tree_sitter::Parser> = RefCell::new({
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_wsv::LANGUAGE.into())
+            .expect("the grammar was generated for a compatible version of tree-sitter");
+        parser
+    });
+}
+
+fn tree(i: &str) -> tree_sitter::Tree {
+    PARSER
+        .with_borrow_mut(|parser| parser.parse(i, None))
+        .expect("no timeout or cancellation flag was set")
+}
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+/// The whole document goes through tree-sitter at once here. Empty lines have no row node, so
+/// every row starts out empty and is filled in from the tree.
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    let tree = tree(i);
+    let mut rows = vec![vec![]; i.split('\n').count()];
+    let mut cursor = tree.walk();
+    for row in tree.root_node().named_children(&mut cursor) {
+        rows[row.start_position().row] = cells(i, 0, row)?;
+    }
+    Ok(rows)
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    let tree = tree(line);
+    let mut cursor = tree.walk();
+    let row = tree.root_node().named_children(&mut cursor).next();
+    match row {
+        Some(row) => cells(line, row_index, row),
+        None => Ok(vec![]),
+    }
+}
+
+/// Tree-sitter counts columns in bytes from the start of the line, so they are counted again in
+/// `char`s.
+fn cells(input: &str, row_index: usize, row: Node) -> Result<Vec<WsvValue>, Error> {
+    let line_start = row.start_byte() - row.start_position().column;
+    let error = |kind, byte: usize| {
+        Error::new(
+            kind,
+            row_index + row.start_position().row + 1,
+            input[line_start..byte].chars().count() + 1,
+            None,
+        )
+    };
+
+    let mut values = vec![];
+    let mut separated = true;
+    let mut cursor = row.walk();
+    for node in row.named_children(&mut cursor) {
+        match node.kind() {
+            "whitespace" => {
+                separated = true;
+                continue;
+            }
+            "comment" => break,
+            _ if !separated => return Err(error(ErrorKind::MissingWhitespace, node.start_byte())),
+            "unclosed_string" => return Err(error(ErrorKind::OddDoubleQuotes, node.end_byte())),
+            _ => values.push(WsvValue::convert(&input[node.byte_range()])),
+        }
+        separated = false;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
@@ -1,375 +1,375 @@
-use crate::ErrorKind;
-use crate::{Error as wsvError, WsvValue};
-fn main() {
-    let mut inputs = vec!['c'].into_iter();
-    let mut state: Box<dyn State> = Box::new(Comment(Scaffold::at_row(0)));
-    let mut ops = vec![];
+//! The state machine from state.rs again, but with each state as its own type.
+//!
+//! In state.rs every state is a variant of one enum, and one big match decides where each of them
+//! goes next, so nothing stops a state from moving anywhere at all. Here every state is a struct,
+//! and its `transition` consumes it and returns its own `State::Next`: either the one state it
+//! always moves to, or an enum listing only the states it may legally move to. `Value` has no way
+//! to reach `Null`, say, because `FromValue` has no `Null` variant, so writing that transition is a
+//! type error. The input still picks which of the legal states comes next at run time; the types
+//! only limit the choice.
+//!
+//! The loop in `parse_line` has to hold whichever state it is in, so the states are gathered into
+//! `Any` to be driven. That is the only place they all meet, and apart from the `Default` it starts
+//! in, every state it holds came out of a `transition`.
+//!
+//! The states themselves do not own the row being built either. That lives in `Scaffold`, and the
+//! state we have just moved into decides what to do to it with the input that got us there.
 
+use crate::data_model::*;
+
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, line): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    let mut scaffold = Scaffold::at_row(row_index);
+    let mut inputs = line.chars();
+    let mut state = Any::Default(Default);
     loop {
         let next_input = inputs.next();
         state = state.transition(next_input);
-        ops.push(state.modify_with(next_input));
+        state.modify(&mut scaffold, next_input);
         if next_input.is_none() {
             break;
         }
     }
+    scaffold.finish()
 }
 
-trait State {
-    fn transition(self, input: Option<char>) -> Box<dyn State>;
-    fn modify_with(&mut self, input: Option<char>);
-}
-
-struct Comment(Scaffold);
-impl State for Comment {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            Some('\n') => Box::new(EndOfLine(self.0)),
-            None => Box::new(Finished(self.0)),
-            _ => Box::new(self),
-        }
-    }
-    fn modify_with(&mut self, input: Option<char>) {
-        self.0.col += 1;
-        self.0.buf.push(input.unwrap());
+/// `None` is the end of the line.
+trait State: Into<Any> {
+    /// The states this one may move to.
+    type Next: Into<Any>;
+    fn transition(self, input: Option<char>) -> Self::Next;
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
     }
 }
 
-struct Default(Scaffold);
-impl State for Default {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            Some('\n') => Box::new(EndOfLine(self.0)),
-            None => Box::new(Finished(self.0)),
-            _ => Box::new(self),
+/// Declares `Any`, which can hold every state, and drives whichever one it holds.
+macro_rules! states {
+    ($($state:ident),* $(,)?) => {
+        enum Any {
+            $($state($state)),*
         }
-    }
-    fn modify_with(&mut self, input: Option<char>) {
-        self.0.col += 1;
-        self.0.buf.push(input.unwrap());
-    }
+        impl Any {
+            fn transition(self, input: Option<char>) -> Any {
+                match self {
+                    $(Any::$state(state) => state.transition(input).into()),*
+                }
+            }
+            fn modify(&self, scaffold: &mut Scaffold, input: Option<char>) {
+                match self {
+                    $(Any::$state(state) => state.modify(scaffold, input)),*
+                }
+            }
+        }
+        $(impl From<$state> for Any {
+            fn from(state: $state) -> Any {
+                Any::$state(state)
+            }
+        })*
+    };
 }
 
-struct EndOfLine(Scaffold);
-impl State for EndOfLine {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::Finished(self.0)),
-            Some('\n') => Box::new(State::EndOfLine(self.0)),
-            Some('#') => Box::new(State::Comment(self.0)),
-            Some('-') => Box::new(State::MayBeNull(self.0)),
-            Some('\"') => Box::new(State::StartString(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::Default(self.0)),
-            Some(_) => Box::new(State::Value(self.0)),
-        }
-    }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0.out.push(vec![]);
-    }
+states! {
+    Default,
+    Comment,
+    Finished,
+    MayBeNull,
+    Null,
+    NullAndComment,
+    Value,
+    EndOfValue,
+    StartString,
+    StringPart,
+    EscapeOrEndOfString,
+    EscapedDoubleQuote,
+    MayBeEscapedReturn,
+    NotAnEscapedReturn,
+    EscapedReturn,
+    Failed,
 }
 
-struct EndOfValue(Scaffold);
-impl State for EndOfValue {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::Finished(self.0)),
-            Some('\n') => Box::new(State::EndOfLine(self.0)),
-            Some('#') => Box::new(State::Comment(self.0)),
-            Some('-') => Box::new(State::MayBeNull(self.0)),
-            Some('\"') => Box::new(State::StartString(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::Default(self.0)),
-            Some(_) => Box::new(State::Value(self.0)),
+/// Declares the enum of states that one state may move to.
+macro_rules! next {
+    ($name:ident { $($state:ident),* $(,)? }) => {
+        enum $name {
+            $($state($state)),*
         }
-    }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0
-            .out
-            .last_mut()
-            .expect("initialised with one")
-            .push(WsvValue::V(self.0.buf.clone()));
-        self.0.buf.clear();
-    }
+        impl From<$name> for Any {
+            fn from(next: $name) -> Any {
+                match next {
+                    $($name::$state(state) => state.into()),*
+                }
+            }
+        }
+    };
 }
 
-struct EndOfValueAndEndOfLine(Scaffold);
-impl State for EndOfValueAndEndOfLine {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
+/// Between cells, or at the start of the line.
+struct Default;
+next!(FromDefault { Finished, MayBeNull, StartString, Default, Value });
+impl State for Default {
+    type Next = FromDefault;
+    fn transition(self, input: Option<char>) -> FromDefault {
         match input {
-            None => Box::new(State::Finished(self.0)),
-            Some('\n') => Box::new(State::EndOfLine(self.0)),
-            Some('#') => Box::new(State::Comment(self.0)),
-            Some('-') => Box::new(State::MayBeNull(self.0)),
-            Some('\"') => Box::new(State::StartString(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::Default(self.0)),
-            Some(_) => Box::new(State::Value(self.0)),
+            None | Some('#') => FromDefault::Finished(Finished),
+            Some('-') => FromDefault::MayBeNull(MayBeNull),
+            Some('"') => FromDefault::StartString(StartString),
+            Some(c) if c.is_whitespace() => FromDefault::Default(self),
+            Some(_) => FromDefault::Value(Value),
         }
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0
-            .out
-            .last_mut()
-            .expect("initialised with one")
-            .push(WsvValue::V(self.0.buf.clone()));
-        self.0.out.push(vec![]);
-        self.0.buf.clear();
-    }
 }
 
-struct Error(Scaffold, ErrorKind);
-impl State for Error {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        State::Finished
+/// Once a comment has started, the rest of the line is ignored.
+struct Comment;
+impl State for Comment {
+    type Next = Finished;
+    fn transition(self, _input: Option<char>) -> Finished {
+        Finished
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0.err = Some(wsvError::new(self.1, self.0.row, self.0.col, None));
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.push_buf();
     }
 }
 
-struct EscapeOrEndOfString(Scaffold);
-impl State for EscapeOrEndOfString {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::EndOfValue(self.0)),
-            Some('\n') => Box::new(State::EndOfValueAndEndOfLine(self.0)),
-            Some('#') => Box::new(State::StartComment(self.0)),
-            Some('\"') => Box::new(State::EscapedDoubleQuote(self.0)),
-            Some('/') => Box::new(State::MayBeEscapedReturn(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::EndOfValue(self.0)),
-            _ => Box::new(State::Error(self.0, ErrorKind::MissingWhitespace)),
-        }
-    }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
+struct Finished;
+impl State for Finished {
+    type Next = Finished;
+    fn transition(self, _input: Option<char>) -> Finished {
+        self
     }
 }
 
-struct EscapedDoubleQuote(Scaffold);
-impl State for EscapedDoubleQuote {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
+struct MayBeNull;
+next!(FromMayBeNull { Null, Failed, NullAndComment, Value });
+impl State for MayBeNull {
+    type Next = FromMayBeNull;
+    fn transition(self, input: Option<char>) -> FromMayBeNull {
         match input {
-            None => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\n') => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\"') => Box::new(State::EscapeOrEndOfString(self.0)),
-            Some(_) => Box::new(State::StringPart(self.0)),
+            None => FromMayBeNull::Null(Null),
+            Some(c) if c.is_whitespace() => FromMayBeNull::Null(Null),
+            Some('"') => FromMayBeNull::Failed(Failed(ErrorKind::MissingWhitespace)),
+            Some('#') => FromMayBeNull::NullAndComment(NullAndComment),
+            Some(_) => FromMayBeNull::Value(Value),
         }
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0.buf.push('\"');
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.buf.push('-');
     }
 }
 
-struct EscapedReturn(Scaffold);
-impl State for EscapedReturn {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\n') => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\"') => Box::new(State::EscapeOrEndOfString(self.0)),
-            Some(_) => Box::new(State::StringPart(self.0)),
-        }
+/// Having pushed the null, carry on as if between cells.
+struct Null;
+impl State for Null {
+    type Next = FromDefault;
+    fn transition(self, input: Option<char>) -> FromDefault {
+        Default.transition(input)
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0.buf.push('\n');
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.out.push(WsvValue::Null);
+        scaffold.buf.clear();
     }
 }
 
-struct Finished(Scaffold);
-impl State for Finished {
-    fn transition(self, _input: Option<char>) -> Box<dyn State> {
-        Box::new(self)
+/// A null with a comment straight after it, as in `-#`.
+struct NullAndComment;
+impl State for NullAndComment {
+    type Next = Finished;
+    fn transition(self, _input: Option<char>) -> Finished {
+        Finished
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
+    fn modify(&self, scaffold: &mut Scaffold, input: Option<char>) {
+        Null.modify(scaffold, input);
     }
 }
 
-struct MayBeEscapedReturn(Scaffold);
-impl State for MayBeEscapedReturn {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
+struct Value;
+next!(FromValue { EndOfValue, Failed, Comment, Value });
+impl State for Value {
+    type Next = FromValue;
+    fn transition(self, input: Option<char>) -> FromValue {
         match input {
-            Some('\"') => Box::new(State::EscapedReturn(self.0)),
-            _ => Box::new(State::Error(self.0, ErrorKind::MissingWhitespace)),
+            None => FromValue::EndOfValue(EndOfValue),
+            Some('"') => FromValue::Failed(Failed(ErrorKind::MissingWhitespace)),
+            Some('#') => FromValue::Comment(Comment),
+            Some(c) if c.is_whitespace() => FromValue::EndOfValue(EndOfValue),
+            Some(_) => FromValue::Value(self),
         }
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
+    fn modify(&self, scaffold: &mut Scaffold, input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.buf.extend(input);
     }
 }
 
-struct MayBeNull(Scaffold);
-impl State for MayBeNull {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::Null(self.0)),
-            Some('\n') => Box::new(State::NullEndOfLine(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::Null(self.0)),
-            Some('\"') => Box::new(State::Error(self.0, ErrorKind::MissingWhitespace)),
-            Some(_) => Box::new(State::Value(self.0)),
-        }
+/// Having pushed the value, carry on as if between cells.
+struct EndOfValue;
+impl State for EndOfValue {
+    type Next = FromDefault;
+    fn transition(self, input: Option<char>) -> FromDefault {
+        Default.transition(input)
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0.buf.push('-');
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.push_buf();
     }
 }
 
-struct Null(Scaffold);
-impl State for Null {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::Finished(self.0)),
-            Some('\n') => Box::new(State::EndOfLine(self.0)),
-            Some('#') => Box::new(State::EndOfLine(self.0)),
-            Some('-') => Box::new(State::MayBeNull(self.0)),
-            Some('\"') => Box::new(State::StartString(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::Default(self.0)),
-            Some(_) => Box::new(State::Value(self.0)),
-        }
-    }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0
-            .out
-            .last_mut()
-            .expect("initialised with one")
-            .push(WsvValue::Null);
-        self.0.buf.clear();
+struct StartString;
+impl State for StartString {
+    type Next = FromStringPart;
+    fn transition(self, input: Option<char>) -> FromStringPart {
+        StringPart.transition(input)
     }
 }
 
-struct NullEndOfLine(Scaffold);
-impl State for NullEndOfLine {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
+struct StringPart;
+next!(FromStringPart { Failed, EscapeOrEndOfString, StringPart });
+impl State for StringPart {
+    type Next = FromStringPart;
+    fn transition(self, input: Option<char>) -> FromStringPart {
         match input {
-            None => Box::new(State::Finished(self.0)),
-            Some('\n') => Box::new(State::EndOfLine(self.0)),
-            Some('#') => Box::new(State::Comment(self.0)),
-            Some('-') => Box::new(State::MayBeNull(self.0)),
-            Some('\"') => Box::new(State::StartString(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::Default(self.0)),
-            Some(_) => Box::new(State::Value(self.0)),
+            None => FromStringPart::Failed(Failed(ErrorKind::OddDoubleQuotes)),
+            Some('"') => FromStringPart::EscapeOrEndOfString(EscapeOrEndOfString),
+            Some(_) => FromStringPart::StringPart(self),
         }
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0
-            .out
-            .last_mut()
-            .expect("initialised with one")
-            .push(WsvValue::V(self.0.buf.clone()));
-        self.0.out.push(vec![]);
-        self.0.buf.clear();
+    fn modify(&self, scaffold: &mut Scaffold, input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.buf.extend(input);
     }
 }
 
-struct StartComment(Scaffold);
-impl State for StartComment {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
+/// A quote inside a string either closes it, or is the start of `""` or `"/"`.
+struct EscapeOrEndOfString;
+next!(FromEscapeOrEndOfString {
+    EndOfValue,
+    Comment,
+    EscapedDoubleQuote,
+    MayBeEscapedReturn,
+    Failed,
+});
+impl State for EscapeOrEndOfString {
+    type Next = FromEscapeOrEndOfString;
+    fn transition(self, input: Option<char>) -> FromEscapeOrEndOfString {
+        use FromEscapeOrEndOfString as Next;
         match input {
-            Some('\n') => Box::new(State::EndOfLine(self.0)),
-            None => Box::new(State::Finished(self.0)),
-            _ => Box::new(State::Comment(self.0)),
+            None => Next::EndOfValue(EndOfValue),
+            Some('#') => Next::Comment(Comment),
+            Some('"') => Next::EscapedDoubleQuote(EscapedDoubleQuote),
+            Some('/') => Next::MayBeEscapedReturn(MayBeEscapedReturn),
+            Some(c) if c.is_whitespace() => Next::EndOfValue(EndOfValue),
+            Some(_) => Next::Failed(Failed(ErrorKind::MissingWhitespace)),
         }
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
-        self.0
-            .out
-            .last_mut()
-            .expect("initialised with one")
-            .push(WsvValue::V(self.0.buf.clone()));
-        self.0.buf.clear();
+}
+
+struct EscapedDoubleQuote;
+impl State for EscapedDoubleQuote {
+    type Next = FromStringPart;
+    fn transition(self, input: Option<char>) -> FromStringPart {
+        StringPart.transition(input)
+    }
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.buf.push('"');
     }
 }
 
-struct StartString(Scaffold);
-impl State for StartString {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
+struct MayBeEscapedReturn;
+next!(FromMayBeEscapedReturn { EscapedReturn, NotAnEscapedReturn });
+impl State for MayBeEscapedReturn {
+    type Next = FromMayBeEscapedReturn;
+    fn transition(self, input: Option<char>) -> FromMayBeEscapedReturn {
         match input {
-            None => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\n') => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\"') => Box::new(State::EscapeOrEndOfString(self.0)),
-            Some(_) => Box::new(State::StringPart(self.0)),
+            Some('"') => FromMayBeEscapedReturn::EscapedReturn(EscapedReturn),
+            _ => FromMayBeEscapedReturn::NotAnEscapedReturn(NotAnEscapedReturn),
         }
     }
-    fn modify_with(&mut self, _input: Option<char>) {
-        self.0.col += 1;
+}
+
+/// The `/` after a string was a cell with no whitespace before it after all. That only shows one
+/// `char` later, so unlike `Failed`, the error stays on the column before.
+struct NotAnEscapedReturn;
+impl State for NotAnEscapedReturn {
+    type Next = Finished;
+    fn transition(self, _input: Option<char>) -> Finished {
+        Finished
+    }
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        let (row, col) = (scaffold.row, scaffold.col);
+        scaffold.err = Some(Error::new(ErrorKind::MissingWhitespace, row, col, None));
     }
 }
 
-struct StringPart(Scaffold);
-impl State for StringPart {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\n') => Box::new(State::Error(self.0, ErrorKind::OddDoubleQuotes)),
-            Some('\"') => Box::new(State::EscapeOrEndOfString(self.0)),
-            Some(_) => Box::new(State::StringPart(self.0)),
-        }
+struct EscapedReturn;
+impl State for EscapedReturn {
+    type Next = FromStringPart;
+    fn transition(self, input: Option<char>) -> FromStringPart {
+        StringPart.transition(input)
     }
-    fn modify_with(&mut self, input: Option<char>) {
-        self.0.col += 1;
-        if let Some(c) = input {
-            self.0.buf.push(c);
-        }
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.buf.push('\n');
     }
 }
 
-struct Value(Scaffold);
-impl State for Value {
-    fn transition(self, input: Option<char>) -> Box<dyn State> {
-        match input {
-            None => State::EndOfValue,
-            Some('\n') => Box::new(State::EndOfValueAndEndOfLine(self.0)),
-            Some('\"') => Box::new(State::Error(self.0, ErrorKind::MissingWhitespace)),
-            Some('#') => Box::new(State::StartComment(self.0)),
-            Some(c) if c.is_whitespace() => Box::new(State::EndOfValue(self.0)),
-            Some(_) => Box::new(State::Value(self.0)),
-        }
+/// The only state with any data in it. There is no way out of here except to finish.
+struct Failed(ErrorKind);
+impl State for Failed {
+    type Next = Finished;
+    fn transition(self, _input: Option<char>) -> Finished {
+        Finished
     }
-    fn modify_with(&mut self, input: Option<char>) {
-        self.0.col += 1;
-        if let Some(c) = input {
-            self.0.buf.push(c);
-        }
+    fn modify(&self, scaffold: &mut Scaffold, _input: Option<char>) {
+        scaffold.col += 1;
+        scaffold.err = Some(Error::new(self.0, scaffold.row, scaffold.col, None));
     }
 }
 
-/// The data structure which gets incrementally modified by the outputs, and produces the final parser output.
-#[derive(Debug, Default)]
+/// The data structure which gets incrementally modified by the states, and produces the final parser output.
+#[derive(Debug)]
 struct Scaffold {
     row: usize,
     col: usize,
     buf: String,
-    out: Vec<Vec<WsvValue>>,
-    err: Option<wsvError>,
+    out: Vec<WsvValue>,
+    err: Option<Error>,
 }
+
 impl Scaffold {
     fn at_row(idx: usize) -> Scaffold {
         Scaffold {
             row: 1 + idx,
             col: 0,
             buf: String::new(),
-            out: vec![vec![]],
+            out: vec![],
             err: None,
         }
     }
-    fn finish(self) -> Result<Vec<Vec<WsvValue>>, wsvError> {
-        match self.err {
-            Some(e) => Err(dbg!(e)),
-            None => Ok(self.out),
-        }
+    fn push_buf(&mut self) {
+        self.out.push(WsvValue::V(std::mem::take(&mut self.buf)));
     }
-    fn finish_row(mut self) -> Result<Vec<WsvValue>, wsvError> {
+    fn finish(self) -> Result<Vec<WsvValue>, Error> {
         match self.err {
-            Some(e) => Err(dbg!(e)),
-            None => Ok(self.out.pop().unwrap()),
+            Some(e) => Err(e),
+            None => Ok(self.out),
         }
     }
 }
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
@@ -0,0 +1,122 @@
+//! This is the nom grammar, ported to winnow.
+//!
+//! Winnow started life as a fork of nom, so the combinators read almost identically. The two
+//! differences that matter here are that parsers take `&mut &str` and advance it in place, and
+//! that the error type is pluggable. `ContextError` is generic over its context, so rather than
+//! attaching strings I attach the `ErrorKind` itself with `.context(...)`, and use `cut_err` to
+//! stop `alt` from backtracking past the point where we know what went wrong. The failing offset
+//! comes for free with `ParseError`, which is everything nom was missing.
+
+use winnow::{
+    combinator::{alt, cut_err, delimited, eof, not, opt, peek, preceded, repeat, separated},
+    error::{ContextError, ParseError},
+    token::{any, literal, take_till, take_while},
+    ModalResult, Parser,
+};
+
+use crate::data_model::*;
+
+type WResult<O> = ModalResult<O, ContextError<ErrorKind>>;
+
+pub fn parse(i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+    i.split('\n').enumerate().map(parse_line).collect()
+}
+
+/// As with nom, newlines are handled by the grammar here too, rather than by splitting first.
+pub fn parse_strict(i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+    separated(0.., line, '\n')
+        .parse(i)
+        .map_err(|e| to_error(i, 0, e))
+}
+
+// we assume that line has no `\n`.
+pub fn parse_line((row_index, input): (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+    line.parse(input).map_err(|e| to_error(input, row_index, e))
+}
+
+/// The offset in a `ParseError` is in bytes from the start of the input, so this turns it into
+/// a row and a column counted in `char`s.
+fn to_error(input: &str, row_index: usize, e: ParseError<&str, ContextError<ErrorKind>>) -> Error {
+    let before = &input[..e.offset()];
+    let row = row_index + before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = before[line_start..].chars().count() + 1;
+    let kind = e
+        .inner()
+        .context()
+        .next()
+        .copied()
+        .unwrap_or(ErrorKind::MissingWhitespace);
+    Error::new(kind, row, col, None)
+}
+
+/// Values must be separated by whitespace, so anything left over after the last one means some
+/// was missing. That error is cut, otherwise `separated` in `parse_strict` would quietly backtrack
+/// to the start of the line and report it there.
+fn line(i: &mut &str) -> WResult<Vec<WsvValue>> {
+    let o = delimited(ws0, separated(0.., alt((nul, string, value)), ws1), ws0).parse_next(i)?;
+    opt(comment).parse_next(i)?;
+    cut_err(peek(alt((eof, "\n"))))
+        .context(ErrorKind::MissingWhitespace)
+        .parse_next(i)?;
+    Ok(o)
+}
+
+fn ws0<'i>(i: &mut &'i str) -> WResult<&'i str> {
+    take_while(0.., |c: char| c.is_whitespace() && c != '\n').parse_next(i)
+}
+
+fn ws1<'i>(i: &mut &'i str) -> WResult<&'i str> {
+    take_while(1.., |c: char| c.is_whitespace() && c != '\n').parse_next(i)
+}
+
+/// A dash is only a null if the value ends there, so `-1` is a value.
+fn nul(i: &mut &str) -> WResult<WsvValue> {
+    ('-', not(value_char)).value(WsvValue::Null).parse_next(i)
+}
+
+/// Once we have seen the opening quote, this can only be a string, so a missing closing quote
+/// is a hard error rather than a reason to try the next alternative.
+fn string(i: &mut &str) -> WResult<WsvValue> {
+    preceded(
+        '"',
+        cut_err((
+            repeat(0.., alt((string_part, double_quote, new_line))),
+            literal('"').context(ErrorKind::OddDoubleQuotes),
+        )),
+    )
+    .map(|(parts, _): (Vec<&str>, _)| WsvValue::V(parts.concat()))
+    .parse_next(i)
+}
+
+fn string_part<'i>(i: &mut &'i str) -> WResult<&'i str> {
+    take_till(1.., ['\n', '"']).parse_next(i)
+}
+
+fn double_quote<'i>(i: &mut &'i str) -> WResult<&'i str> {
+    "\"\"".value("\"").parse_next(i)
+}
+
+fn new_line<'i>(i: &mut &'i str) -> WResult<&'i str> {
+    "\"/\"".value("\n").parse_next(i)
+}
+
+fn value_char(i: &mut &str) -> WResult<char> {
+    any.verify(|c: &char| !(*c == '"' || *c == '#' || c.is_whitespace()))
+        .parse_next(i)
+}
+
+fn value(i: &mut &str) -> WResult<WsvValue> {
+    take_till(1.., |c: char| c == '"' || c == '#' || c.is_whitespace())
+        .map(|s: &str| WsvValue::V(String::from(s)))
+        .parse_next(i)
+}
+
+fn comment<'i>(i: &mut &'i str) -> WResult<&'i str> {
+    preceded('#', take_till(0.., '\n')).parse_next(i)
+}
+
+#[cfg(test)]
+use crate::unit;
+#[cfg(test)]
+unit! {}
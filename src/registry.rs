@@ -0,0 +1,377 @@
+//! Every implementation in one place, so that one can be picked at runtime.
+//!
+//! Each parser module has the same three functions, but calling them directly means deciding at
+//! compile time which one you want. `Parser` names every implementation compiled into this build,
+//! and through `WsvParser` it can be used just like the modules themselves, or asked what it is.
+//! It can also be parsed from its name, so a command line flag or a config file can choose.
+//!
+//! Neither the modules' functions nor `parse`, `parse_strict` and `parse_line` here check any
+//! `ParseLimits`, and they will take input of any size. For untrusted input, only the
+//! `_with_limits` methods are safe.
+//!
+//! The default is whichever available parser was fastest in the `reader_one_big_line` benchmark.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::data_model::*;
+use crate::limits::line_by_line;
+use crate::telemetry;
+use crate::ParseLimits;
+
+/// Anything which parses WSV, along with a little about how it does it.
+pub trait WsvParser {
+    /// Every row, with any errors in place of the rows they were found on. Checks no limits.
+    fn parse(&self, i: &str) -> Vec<Result<Vec<WsvValue>, Error>>;
+    /// Every row, or the first error. Checks no limits.
+    fn parse_strict(&self, i: &str) -> Result<Vec<Vec<WsvValue>>, Error>;
+    /// A single line, which should have no `\n` in it, along with its index in the document.
+    /// Checks no limits.
+    fn parse_line(&self, line: (usize, &str)) -> Result<Vec<WsvValue>, Error>;
+
+    /// As `parse`, but one line at a time, each checked against `limits` before it is parsed.
+    /// Stops with an `ErrorKind::LimitExceeded` at the first breach, as the readers do.
+    fn parse_with_limits(&self, i: &str, limits: ParseLimits) -> Vec<Result<Vec<WsvValue>, Error>> {
+        line_by_line(i, limits, |line| self.parse_line_with_limits(line, limits))
+    }
+    /// As `parse_strict`, but every row, or the first error or breached limit.
+    fn parse_strict_with_limits(
+        &self,
+        i: &str,
+        limits: ParseLimits,
+    ) -> Result<Vec<Vec<WsvValue>>, Error> {
+        self.parse_with_limits(i, limits).into_iter().collect()
+    }
+    /// As `parse_line`, but only once the line is within `limits`.
+    fn parse_line_with_limits(
+        &self,
+        line: (usize, &str),
+        limits: ParseLimits,
+    ) -> Result<Vec<WsvValue>, Error> {
+        limits.check_line(line).and_then(|_| self.parse_line(line))
+    }
+
+    /// The name it is known by, which is also the name of its module.
+    fn name(&self) -> &'static str;
+    /// The crate it showcases, or `None` if it only needs the standard library.
+    fn crate_name(&self) -> Option<&'static str>;
+    fn category(&self) -> Category;
+    /// The cargo feature it is behind, or `None` if it is always available.
+    fn feature(&self) -> Option<&'static str>;
+}
+
+/// The broad approach an implementation takes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Written by hand, with no particular design to it.
+    HandWritten,
+    /// An explicit state machine, fed one `char` at a time.
+    StateMachine,
+    /// Built from small parsers, composed with functions.
+    Combinator,
+    /// Generated from a grammar, at build time or by a macro.
+    Grammar,
+    /// A generated lexer, with a parser on top.
+    Lexer,
+    Regex,
+}
+
+/// Every implementation compiled into this build.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Parser {
+    #[cfg(feature = "nom")]
+    Nom,
+    #[cfg(feature = "winnow")]
+    Winnow,
+    #[cfg(feature = "pest")]
+    Pest,
+    #[cfg(feature = "peg")]
+    Peg,
+    #[cfg(feature = "peginator")]
+    Peginator,
+    #[cfg(feature = "logos")]
+    Logos,
+    /// Chumsky recovers from errors, but through here a row with an error is only its error. The
+    /// cells before it are only given back by `wsv::chumsky::parse_recovered`.
+    #[cfg(feature = "chumsky")]
+    Chumsky,
+    #[cfg(feature = "lalrpop")]
+    Lalrpop,
+    #[cfg(feature = "parol")]
+    Parol,
+    #[cfg(feature = "combine")]
+    Combine,
+    #[cfg(feature = "pom")]
+    Pom,
+    #[cfg(feature = "tree-sitter")]
+    TreeSitter,
+    State,
+    Typestate,
+    Mealy,
+    Moore,
+    Split,
+    Regex,
+    First,
+}
+
+impl Parser {
+    /// Every available parser, fastest first, as measured by the `reader_one_big_line` benchmark.
+    pub const ALL: &'static [Parser] = &[
+        #[cfg(feature = "logos")]
+        Parser::Logos,
+        Parser::State,
+        #[cfg(feature = "nom")]
+        Parser::Nom,
+        #[cfg(feature = "peg")]
+        Parser::Peg,
+        Parser::Typestate,
+        Parser::Mealy,
+        Parser::Moore,
+        #[cfg(feature = "lalrpop")]
+        Parser::Lalrpop,
+        Parser::First,
+        #[cfg(feature = "winnow")]
+        Parser::Winnow,
+        #[cfg(feature = "peginator")]
+        Parser::Peginator,
+        #[cfg(feature = "chumsky")]
+        Parser::Chumsky,
+        #[cfg(feature = "combine")]
+        Parser::Combine,
+        #[cfg(feature = "pom")]
+        Parser::Pom,
+        Parser::Regex,
+        #[cfg(feature = "tree-sitter")]
+        Parser::TreeSitter,
+        #[cfg(feature = "parol")]
+        Parser::Parol,
+        Parser::Split,
+        #[cfg(feature = "pest")]
+        Parser::Pest,
+    ];
+
+    /// The fastest parser in this build.
+    pub fn fastest() -> Parser {
+        Parser::ALL[0]
+    }
+
+    fn entry(self) -> Entry {
+        macro_rules! entry {
+            ($module:ident, $crate_name:expr, $category:ident, $feature:expr) => {
+                entry!(
+                    $module,
+                    $crate_name,
+                    $category,
+                    $feature,
+                    crate::$module::parse
+                )
+            };
+            ($module:ident, $crate_name:expr, $category:ident, $feature:expr, $parse:expr) => {
+                Entry {
+                    name: stringify!($module),
+                    crate_name: $crate_name,
+                    category: Category::$category,
+                    feature: $feature,
+                    parse: $parse,
+                    parse_strict: crate::$module::parse_strict,
+                    parse_line: crate::$module::parse_line,
+                }
+            };
+        }
+
+        match self {
+            #[cfg(feature = "nom")]
+            Parser::Nom => entry!(nom, Some("nom"), Combinator, Some("nom")),
+            #[cfg(feature = "winnow")]
+            Parser::Winnow => entry!(winnow, Some("winnow"), Combinator, Some("winnow")),
+            #[cfg(feature = "pest")]
+            Parser::Pest => entry!(pest, Some("pest"), Grammar, Some("pest"), |i| {
+                i.split('\n')
+                    .enumerate()
+                    .map(crate::pest::parse_line)
+                    .collect()
+            }),
+            #[cfg(feature = "peg")]
+            Parser::Peg => entry!(peg, Some("peg"), Grammar, Some("peg")),
+            #[cfg(feature = "peginator")]
+            Parser::Peginator => entry!(peginator, Some("peginator"), Grammar, Some("peginator")),
+            #[cfg(feature = "logos")]
+            Parser::Logos => entry!(logos, Some("logos"), Lexer, Some("logos")),
+            #[cfg(feature = "chumsky")]
+            Parser::Chumsky => entry!(chumsky, Some("chumsky"), Combinator, Some("chumsky")),
+            #[cfg(feature = "lalrpop")]
+            Parser::Lalrpop => entry!(lalrpop, Some("lalrpop"), Grammar, Some("lalrpop")),
+            #[cfg(feature = "parol")]
+            Parser::Parol => entry!(parol, Some("parol"), Grammar, Some("parol")),
+            #[cfg(feature = "combine")]
+            Parser::Combine => entry!(combine, Some("combine"), Combinator, Some("combine")),
+            #[cfg(feature = "pom")]
+            Parser::Pom => entry!(pom, Some("pom"), Combinator, Some("pom")),
+            #[cfg(feature = "tree-sitter")]
+            Parser::TreeSitter => {
+                entry!(
+                    tree_sitter,
+                    Some("tree-sitter"),
+                    Grammar,
+                    Some("tree-sitter")
+                )
+            }
+            Parser::State => entry!(state, None, StateMachine, None),
+            Parser::Typestate => entry!(typestate, None, StateMachine, None),
+            Parser::Mealy => entry!(mealy, None, StateMachine, None),
+            Parser::Moore => entry!(moore, None, StateMachine, None),
+            Parser::Split => entry!(split, Some("itertools"), HandWritten, None),
+            Parser::Regex => entry!(regex, Some("regex"), Regex, None),
+            Parser::First => entry!(first, None, HandWritten, None),
+        }
+    }
+}
+
+/// What every `Parser` is made of. Pest has no `parse` of its own, so it cannot just be the
+/// module functions, and plain function pointers keep it cheap to build.
+struct Entry {
+    name: &'static str,
+    crate_name: Option<&'static str>,
+    category: Category,
+    feature: Option<&'static str>,
+    parse: ParseFn,
+    parse_strict: ParseStrictFn,
+    parse_line: ParseLineFn,
+}
+
+type ParseFn = fn(&str) -> Vec<Result<Vec<WsvValue>, Error>>;
+type ParseStrictFn = fn(&str) -> Result<Vec<Vec<WsvValue>>, Error>;
+type ParseLineFn = fn((usize, &str)) -> Result<Vec<WsvValue>, Error>;
+
+impl WsvParser for Parser {
+    fn parse(&self, i: &str) -> Vec<Result<Vec<WsvValue>, Error>> {
+        let timer = telemetry::document(*self);
+        let out = (self.entry().parse)(i);
+        telemetry::errors(&out);
+        timer.document(&out);
+        out
+    }
+    fn parse_strict(&self, i: &str) -> Result<Vec<Vec<WsvValue>>, Error> {
+        let timer = telemetry::document(*self);
+        let out = (self.entry().parse_strict)(i);
+        telemetry::errors(&out);
+        timer.document(&out);
+        out
+    }
+    fn parse_line(&self, line: (usize, &str)) -> Result<Vec<WsvValue>, Error> {
+        let timer = telemetry::line(*self, line.0);
+        let out = (self.entry().parse_line)(line);
+        timer.line(&out);
+        out
+    }
+    fn parse_with_limits(&self, i: &str, limits: ParseLimits) -> Vec<Result<Vec<WsvValue>, Error>> {
+        let timer = telemetry::document(*self);
+        let out = line_by_line(i, limits, |line| self.parse_line_with_limits(line, limits));
+        timer.document(&out);
+        out
+    }
+    fn parse_line_with_limits(
+        &self,
+        line: (usize, &str),
+        limits: ParseLimits,
+    ) -> Result<Vec<WsvValue>, Error> {
+        let timer = telemetry::line(*self, line.0);
+        let out = limits
+            .check_line(line)
+            .and_then(|_| (self.entry().parse_line)(line));
+        timer.line(&out);
+        out
+    }
+    fn name(&self) -> &'static str {
+        self.entry().name
+    }
+    fn crate_name(&self) -> Option<&'static str> {
+        self.entry().crate_name
+    }
+    fn category(&self) -> Category {
+        self.entry().category
+    }
+    fn feature(&self) -> Option<&'static str> {
+        self.entry().feature
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser::fastest()
+    }
+}
+
+impl fmt::Display for Parser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The name given does not match any parser in this build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownParser(pub String);
+
+impl fmt::Display for UnknownParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "No parser called {:?} in this build. Expected one of:",
+            self.0
+        )?;
+        for parser in Parser::ALL {
+            write!(f, " {parser}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownParser {}
+
+/// Names are matched ignoring case, and `-` may stand in for `_`, so `tree-sitter` works too.
+impl FromStr for Parser {
+    type Err = UnknownParser;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = s.to_ascii_lowercase().replace('-', "_");
+        Parser::ALL
+            .iter()
+            .find(|parser| parser.name() == wanted)
+            .copied()
+            .ok_or_else(|| UnknownParser(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use super::*;
+
+    #[test]
+    fn every_parser_by_name() {
+        for parser in Parser::ALL {
+            assert_eq!(parser.name().parse::<Parser>(), Ok(*parser));
+            assert_eq!(
+                parser.to_string().to_uppercase().parse::<Parser>(),
+                Ok(*parser)
+            );
+        }
+        assert!("nonsense".parse::<Parser>().is_err());
+    }
+
+    #[test]
+    fn every_parser_agrees_on_valid_input() {
+        let input = "a - \"b \"\"c\"\"\"/\"\" # d\n\n  e";
+        let expected = Parser::First.parse_strict(input).unwrap();
+        for parser in Parser::ALL {
+            assert_eq!(parser.parse_strict(input).unwrap(), expected, "{parser}");
+            assert!(parser.parse(input).iter().all(Result::is_ok), "{parser}");
+            assert_eq!(parser.parse_line((0, "a -")).unwrap().len(), 2, "{parser}");
+        }
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn dashes_for_underscores() {
+        assert_eq!("tree-sitter".parse::<Parser>(), Ok(Parser::TreeSitter));
+    }
+}
@@ -0,0 +1,205 @@
+//! Spans and events for `tracing`, so that ingestion can be profiled with whatever subscriber is
+//! already in place. All of it is behind the `tracing` feature, and without it these functions
+//! do nothing at all, and `tracing` is not even compiled.
+//!
+//! - a `document` span, at `info`, around every whole document, with the parser's name on it. When
+//!   it is done there is an `info` event with how many rows and errors it had, and how long it took.
+//! - a `line` span, at `debug`, around every line parsed on its own, with its row. A `trace` event
+//!   says how long it took.
+//! - a `warn` event for every row with an error, with its code, row and column.
+//!
+//! The readers in `io` go one line at a time, so their documents are full of line spans. `parse`
+//! and `parse_strict` hand the whole document to the parser, so they have no line spans in them.
+
+use crate::data_model::*;
+use crate::Parser;
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// Started when a parse begins, and finished with what it gave back.
+#[cfg(feature = "tracing")]
+pub(crate) struct Timer {
+    _span: tracing::span::EnteredSpan,
+    start: Instant,
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Timer;
+
+#[cfg(feature = "tracing")]
+pub(crate) fn document(parser: Parser) -> Timer {
+    Timer {
+        _span: tracing::info_span!("document", %parser).entered(),
+        start: Instant::now(),
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn line(parser: Parser, row_index: usize) -> Timer {
+    Timer {
+        _span: tracing::debug_span!("line", %parser, row = row_index + 1).entered(),
+        start: Instant::now(),
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Timer {
+    pub(crate) fn document<T: Outcome>(self, out: &T) {
+        tracing::info!(
+            rows = out.rows(),
+            errors = out.errors().count(),
+            elapsed_us = self.start.elapsed().as_micros() as u64,
+            "parsed"
+        );
+    }
+
+    pub(crate) fn line(self, out: &Result<Vec<WsvValue>, Error>) {
+        tracing::trace!(
+            elapsed_ns = self.start.elapsed().as_nanos() as u64,
+            "parsed"
+        );
+        errors(out);
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn document(_parser: Parser) -> Timer {
+    Timer
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn line(_parser: Parser, _row_index: usize) -> Timer {
+    Timer
+}
+
+#[cfg(not(feature = "tracing"))]
+impl Timer {
+    #[inline(always)]
+    pub(crate) fn document<T>(self, _out: &T) {}
+    #[inline(always)]
+    pub(crate) fn line(self, _out: &Result<Vec<WsvValue>, Error>) {}
+}
+
+/// A `warn` event for every error in `out`, for documents which were not parsed line by line.
+#[cfg(feature = "tracing")]
+pub(crate) fn errors<T: Outcome>(out: &T) {
+    for e in out.errors() {
+        tracing::warn!(code = e.kind.code(), row = e.row, col = e.col, "{e}");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn errors<T>(_out: &T) {}
+
+/// Whatever a parse gives back, counted up for the summary.
+#[cfg(feature = "tracing")]
+pub(crate) trait Outcome {
+    fn rows(&self) -> usize;
+    fn errors(&self) -> impl Iterator<Item = &Error>;
+}
+
+#[cfg(feature = "tracing")]
+impl Outcome for Vec<Result<Vec<WsvValue>, Error>> {
+    fn rows(&self) -> usize {
+        self.len()
+    }
+    fn errors(&self) -> impl Iterator<Item = &Error> {
+        self.iter().filter_map(|row| row.as_ref().err())
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<T> Outcome for Result<Vec<T>, Error> {
+    fn rows(&self) -> usize {
+        self.as_ref().map_or(0, Vec::len)
+    }
+    fn errors(&self) -> impl Iterator<Item = &Error> {
+        self.as_ref().err().into_iter()
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod telemetry_tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        from_reader_with_parser, from_string_with_parser, ColumnUnit, ParseLimits, Parser,
+        WsvParser,
+    };
+
+    /// Everything a subscriber printed while `f` ran.
+    fn logged(f: impl FnOnce()) -> String {
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+        impl Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn a_document_read_line_by_line() {
+        let log = logged(|| {
+            from_string_with_parser(
+                &mut "a b\n\"c".as_bytes(),
+                Parser::Mealy,
+                ColumnUnit::Chars,
+                ParseLimits::default(),
+            );
+        });
+        assert!(log.contains("document{parser=mealy}:line{parser=mealy row=2}"));
+        assert!(log.contains("code=\"WSV001\" row=2"));
+        assert!(log.contains("rows=2 errors=1"));
+    }
+
+    /// Rows the reader turns away before parsing them still get their `warn` event.
+    #[test]
+    fn rows_which_never_reach_the_parser() {
+        let limits = ParseLimits {
+            max_line_length: Some(3),
+            ..Default::default()
+        };
+        let log = logged(|| {
+            from_reader_with_parser(
+                &mut b"\xC3\x28\nabcdef".as_slice(),
+                Parser::Mealy,
+                ColumnUnit::Chars,
+                limits,
+            );
+        });
+        assert!(log.contains("code=\"WSV005\" row=1 col=1"));
+        assert!(log.contains("WARN document{parser=mealy}: wsv::telemetry: Resource limit"));
+        assert!(log.contains("code=\"WSV004\" row=2 col=4"));
+        assert!(log.contains("rows=2 errors=2"));
+    }
+
+    #[test]
+    fn a_whole_document() {
+        let log = logged(|| {
+            Parser::State.parse_strict("a\n\"b\"c").unwrap_err();
+        });
+        assert!(!log.contains("line{"));
+        assert!(log.contains("code=\"WSV002\" row=2"));
+        assert!(log.contains("document{parser=state}: wsv::telemetry: parsed rows=0 errors=1"));
+    }
+}
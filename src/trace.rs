@@ -0,0 +1,248 @@
+//! Watching a parser work through a line, one `char` at a time.
+//!
+//! The state machines are the easiest to watch, since every `char` moves them from one state to
+//! another and makes them emit a `Transform`, and the whole of their working is just that list.
+//! The grammar parsers do not work that way, so for pest and nom the trace says which rule matched
+//! each `char` instead. Anything left over once no rule matches is where the line went wrong.
+//!
+//! ```
+//! let trace = wsv::trace::explain("a -", wsv::Parser::Mealy).unwrap();
+//! println!("{trace}");
+//! assert_eq!(trace.steps.len(), 4);
+//! ```
+use std::fmt;
+
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::{Attribute, Cell, Color, Table};
+
+use crate::data_model::*;
+use crate::machine::{Mealy, Moore, Step as Transition};
+use crate::{Parser, WsvParser};
+
+/// A line, what happened at each `char` of it, and what it parsed to in the end.
+#[derive(Debug)]
+pub struct Trace {
+    pub parser: Parser,
+    pub line: String,
+    pub steps: Vec<Step>,
+    pub result: Result<Vec<WsvValue>, Error>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Counted in `char`s from 1, as in `Error`.
+    pub col: usize,
+    /// `None` for the end of the line, which the state machines are fed as well.
+    pub input: Option<char>,
+    pub explanation: Explanation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Explanation {
+    Transition {
+        before: String,
+        after: String,
+        output: String,
+    },
+    /// The rules which matched, outermost first.
+    Rules(Vec<String>),
+    /// Nothing matched here, so this is where the grammar gave up.
+    Unmatched,
+}
+
+/// Only the state machines and the two grammars with rules to name can be traced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Untraceable(pub Parser);
+
+impl fmt::Display for Untraceable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} cannot be traced. Try mealy, moore", self.0)?;
+        #[cfg(feature = "pest")]
+        write!(f, ", pest")?;
+        #[cfg(feature = "nom")]
+        write!(f, ", nom")?;
+        write!(f, " instead.")
+    }
+}
+
+impl std::error::Error for Untraceable {}
+
+/// Parses one line with `parser`, noting down every step of the way. The line should have no
+/// `\n` in it.
+pub fn explain(line: &str, parser: Parser) -> Result<Trace, Untraceable> {
+    let input = || line.chars().map(Some).chain([None]);
+    let steps = match parser {
+        Parser::Mealy => transitions(crate::mealy::WsvMachine::stepper().steps(input())),
+        Parser::Moore => transitions(crate::moore::WsvMachine::stepper().steps(input())),
+        #[cfg(feature = "pest")]
+        Parser::Pest => rules(line, crate::pest::rules(line)),
+        #[cfg(feature = "nom")]
+        Parser::Nom => rules(
+            line,
+            crate::nom::rules(line)
+                .into_iter()
+                .flat_map(|(rule, matched)| matched.chars().map(move |_| vec![rule.to_owned()]))
+                .collect(),
+        ),
+        _ => return Err(Untraceable(parser)),
+    };
+    Ok(Trace {
+        parser,
+        line: line.to_owned(),
+        steps,
+        result: parser.parse_line((0, line)),
+    })
+}
+
+fn transitions<S: fmt::Debug, O: fmt::Debug>(
+    steps: impl Iterator<Item = Transition<S, Option<char>, O>>,
+) -> Vec<Step> {
+    steps
+        .enumerate()
+        .map(|(index, step)| Step {
+            col: index + 1,
+            input: step.input,
+            explanation: Explanation::Transition {
+                before: format!("{:?}", step.from),
+                after: format!("{:?}", step.to),
+                output: format!("{:?}", step.output),
+            },
+        })
+        .collect()
+}
+
+/// One step per `char`, with the rules matched at each. There are fewer rules than `char`s when
+/// the grammar gave up part way.
+#[cfg(any(feature = "pest", feature = "nom"))]
+fn rules(line: &str, mut rules: Vec<Vec<String>>) -> Vec<Step> {
+    rules.resize(line.chars().count(), vec![]);
+    line.chars()
+        .zip(rules)
+        .enumerate()
+        .map(|(index, (c, rules))| Step {
+            col: index + 1,
+            input: Some(c),
+            explanation: match rules.is_empty() {
+                true => Explanation::Unmatched,
+                false => Explanation::Rules(rules),
+            },
+        })
+        .collect()
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        let header = match self.steps.first().map(|step| &step.explanation) {
+            Some(Explanation::Transition { .. }) => {
+                vec!["Col", "Input", "Before", "After", "Output"]
+            }
+            _ => vec!["Col", "Input", "Rules"],
+        };
+        table.set_header(header);
+        for step in &self.steps {
+            let input = match step.input {
+                Some(c) => Cell::new(format!("{c:?}")),
+                None => Cell::new("end").add_attribute(Attribute::Italic),
+            };
+            let mut row = vec![Cell::new(step.col), input];
+            match &step.explanation {
+                Explanation::Transition {
+                    before,
+                    after,
+                    output,
+                } => row.extend([Cell::new(before), Cell::new(after), Cell::new(output)]),
+                Explanation::Rules(rules) => row.push(Cell::new(rules.join(" › "))),
+                Explanation::Unmatched => row.push(
+                    Cell::new("no rule matches")
+                        .add_attribute(Attribute::Bold)
+                        .fg(Color::DarkRed),
+                ),
+            }
+            table.add_row(row);
+        }
+        writeln!(f, "{} on {:?}", self.parser, self.line)?;
+        writeln!(f, "{table}")?;
+
+        let mut result = Table::new();
+        result.apply_modifier(UTF8_ROUND_CORNERS);
+        match &self.result {
+            Ok(values) => result.add_row(values.iter().map(value_cell)),
+            Err(e) => result.add_row(vec![error_cell(e)]),
+        };
+        write!(f, "{result}")
+    }
+}
+
+#[cfg(test)]
+mod trace_tests {
+    use super::*;
+
+    #[test]
+    fn mealy() {
+        let trace = explain("-", Parser::Mealy).unwrap();
+        assert_eq!(
+            trace.steps[1],
+            Step {
+                col: 2,
+                input: None,
+                explanation: Explanation::Transition {
+                    before: "MayBeNull".into(),
+                    after: "Null".into(),
+                    output: "AddNull".into(),
+                },
+            }
+        );
+        assert_eq!(trace.result.unwrap(), vec![WsvValue::Null]);
+    }
+
+    #[test]
+    fn moore_keeps_the_char_in_its_state() {
+        let trace = explain("a", Parser::Moore).unwrap();
+        assert!(matches!(
+            &trace.steps[0].explanation,
+            Explanation::Transition { after, .. } if after == "Value('a')"
+        ));
+    }
+
+    #[cfg(feature = "pest")]
+    #[test]
+    fn pest() {
+        let trace = explain("\"\"\"\" a\"b\"", Parser::Pest).unwrap();
+        let rules = |col: usize| trace.steps[col - 1].explanation.clone();
+        assert_eq!(
+            rules(2),
+            Explanation::Rules(vec!["Line".into(), "String".into(), "DoubleQuote".into()])
+        );
+        assert_eq!(rules(5), Explanation::Rules(vec!["Line".into()]));
+        assert_eq!(rules(7), Explanation::Unmatched);
+        assert!(trace.result.is_err());
+    }
+
+    #[cfg(feature = "nom")]
+    #[test]
+    fn nom() {
+        let trace = explain(" - b #c", Parser::Nom).unwrap();
+        let names = trace
+            .steps
+            .iter()
+            .map(|step| match &step.explanation {
+                Explanation::Rules(rules) => rules.join(""),
+                _ => "?".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["ws0", "nul", "ws1", "value", "ws0", "comment", "comment"]
+        );
+    }
+
+    #[test]
+    fn untraceable() {
+        assert_eq!(
+            explain("a", Parser::First).unwrap_err(),
+            Untraceable(Parser::First)
+        );
+    }
+}
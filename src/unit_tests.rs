@@ -4,10 +4,10 @@ macro_rules! unit {
         #[cfg(test)]
         mod tests {
             use super::parse_strict;
-            use crate::data_model::Error;
-            use crate::data_model::ErrorKind::*;
-            use crate::data_model::WsvValue;
-            use crate::data_model::WsvValue::Null;
+            use $crate::data_model::Error;
+            use $crate::data_model::ErrorKind::*;
+            use $crate::data_model::WsvValue;
+            use $crate::data_model::WsvValue::Null;
 
             macro_rules! do_test {
                 ($parser:ident, $input:expr, $output:expr) => {
@@ -0,0 +1,52 @@
+/**
+ * @file Whitespace-separated values, as defined by Stenway.
+ * @author honestlysamuk
+ * @license MIT
+ */
+
+/// <reference types="tree-sitter-cli/dsl" />
+// @ts-check
+
+// Every character Rust's `char::is_whitespace` accepts, apart from the line feed which ends a
+// row. Spelling them out means no external scanner is needed.
+const WHITESPACE = '\\t\\x0B\\x0C\\r \\u0085\\u00A0\\u1680\\u2000-\\u200A\\u2028\\u2029\\u202F\\u205F\\u3000';
+
+// The inside of a string, where `""` is a quote and `"/"` is a line feed.
+const STRING_PART = '([^"\\n]|""|"/")*';
+
+module.exports = grammar({
+  name: 'wsv',
+
+  // Whitespace means something here, so nothing is skipped.
+  extras: _ => [],
+
+  rules: {
+    document: $ => seq(optional($.row), repeat(seq('\n', optional($.row)))),
+
+    // A row does not insist on whitespace between its cells, so that a mistake in one cell does
+    // not spoil the highlighting of the rest of the file. Checking for it is left to whoever
+    // walks the tree.
+    row: $ => repeat1(choice(
+      $.whitespace,
+      $.null,
+      $.value,
+      $.string,
+      $.unclosed_string,
+      $.comment,
+    )),
+
+    whitespace: _ => token(new RegExp(`[${WHITESPACE}]+`)),
+
+    // A lone dash is also a value, but string literals win ties against regexes.
+    null: _ => '-',
+
+    value: _ => token(new RegExp(`[^${WHITESPACE}\\n"#]+`)),
+
+    string: _ => token(new RegExp(`"${STRING_PART}"`)),
+
+    // The lexer always takes the longest match, so this only wins when there is no closing quote.
+    unclosed_string: _ => token(new RegExp(`"${STRING_PART}`)),
+
+    comment: _ => token(/#[^\n]*/),
+  },
+});
@@ -0,0 +1,9 @@
+(comment) @comment
+
+(null) @constant.builtin
+
+(string) @string
+
+(unclosed_string) @error
+
+(value) @string.special
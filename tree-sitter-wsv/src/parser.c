@@ -0,0 +1,395 @@
+/* Automatically @generated by tree-sitter */
+
+#include "tree_sitter/parser.h"
+
+#if defined(__GNUC__) || defined(__clang__)
+#pragma GCC diagnostic ignored "-Wmissing-field-initializers"
+#endif
+
+#define LANGUAGE_VERSION 15
+#define STATE_COUNT 11
+#define LARGE_STATE_COUNT 5
+#define SYMBOL_COUNT 12
+#define ALIAS_COUNT 0
+#define TOKEN_COUNT 8
+#define EXTERNAL_TOKEN_COUNT 0
+#define FIELD_COUNT 0
+#define MAX_ALIAS_SEQUENCE_LENGTH 2
+#define MAX_RESERVED_WORD_SET_SIZE 0
+#define PRODUCTION_ID_COUNT 1
+#define SUPERTYPE_COUNT 0
+
+enum ts_symbol_identifiers {
+  anon_sym_LF = 1,
+  sym_whitespace = 2,
+  sym_null = 3,
+  sym_value = 4,
+  sym_string = 5,
+  sym_unclosed_string = 6,
+  sym_comment = 7,
+  sym_document = 8,
+  sym_row = 9,
+  aux_sym_document_repeat1 = 10,
+  aux_sym_row_repeat1 = 11,
+};
+
+static const char * const ts_symbol_names[] = {
+  [ts_builtin_sym_end] = "end",
+  [anon_sym_LF] = "\n",
+  [sym_whitespace] = "whitespace",
+  [sym_null] = "null",
+  [sym_value] = "value",
+  [sym_string] = "string",
+  [sym_unclosed_string] = "unclosed_string",
+  [sym_comment] = "comment",
+  [sym_document] = "document",
+  [sym_row] = "row",
+  [aux_sym_document_repeat1] = "document_repeat1",
+  [aux_sym_row_repeat1] = "row_repeat1",
+};
+
+static const TSSymbol ts_symbol_map[] = {
+  [ts_builtin_sym_end] = ts_builtin_sym_end,
+  [anon_sym_LF] = anon_sym_LF,
+  [sym_whitespace] = sym_whitespace,
+  [sym_null] = sym_null,
+  [sym_value] = sym_value,
+  [sym_string] = sym_string,
+  [sym_unclosed_string] = sym_unclosed_string,
+  [sym_comment] = sym_comment,
+  [sym_document] = sym_document,
+  [sym_row] = sym_row,
+  [aux_sym_document_repeat1] = aux_sym_document_repeat1,
+  [aux_sym_row_repeat1] = aux_sym_row_repeat1,
+};
+
+static const TSSymbolMetadata ts_symbol_metadata[] = {
+  [ts_builtin_sym_end] = {
+    .visible = false,
+    .named = true,
+  },
+  [anon_sym_LF] = {
+    .visible = true,
+    .named = false,
+  },
+  [sym_whitespace] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_null] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_value] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_string] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_unclosed_string] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_comment] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_document] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_row] = {
+    .visible = true,
+    .named = true,
+  },
+  [aux_sym_document_repeat1] = {
+    .visible = false,
+    .named = false,
+  },
+  [aux_sym_row_repeat1] = {
+    .visible = false,
+    .named = false,
+  },
+};
+
+static const TSSymbol ts_alias_sequences[PRODUCTION_ID_COUNT][MAX_ALIAS_SEQUENCE_LENGTH] = {
+  [0] = {0},
+};
+
+static const uint16_t ts_non_terminal_alias_map[] = {
+  0,
+};
+
+static const TSStateId ts_primary_state_ids[STATE_COUNT] = {
+  [0] = 0,
+  [1] = 1,
+  [2] = 2,
+  [3] = 3,
+  [4] = 4,
+  [5] = 5,
+  [6] = 6,
+  [7] = 7,
+  [8] = 8,
+  [9] = 9,
+  [10] = 10,
+};
+
+static const TSCharacterRange sym_whitespace_character_set_1[] = {
+  {'\t', '\t'}, {0x0b, '\r'}, {' ', ' '}, {0x85, 0x85}, {0xa0, 0xa0}, {0x1680, 0x1680}, {0x2000, 0x200a}, {0x2028, 0x2029},
+  {0x202f, 0x202f}, {0x205f, 0x205f}, {0x3000, 0x3000},
+};
+
+static const TSCharacterRange sym_value_character_set_1[] = {
+  {0, 0x08}, {0x0e, 0x1f}, {'!', '!'}, {'$', 0x84}, {0x86, 0x9f}, {0xa1, 0x167f}, {0x1681, 0x1fff}, {0x200b, 0x2027},
+  {0x202a, 0x202e}, {0x2030, 0x205e}, {0x2060, 0x2fff}, {0x3001, 0x10ffff},
+};
+
+static bool ts_lex(TSLexer *lexer, TSStateId state) {
+  START_LEXER();
+  eof = lexer->eof(lexer);
+  switch (state) {
+    case 0:
+      if (eof) ADVANCE(2);
+      if (lookahead == '\n') ADVANCE(3);
+      if (lookahead == '"') ADVANCE(8);
+      if (lookahead == '#') ADVANCE(9);
+      if (lookahead == '-') ADVANCE(5);
+      if (set_contains(sym_whitespace_character_set_1, 11, lookahead)) ADVANCE(4);
+      if (lookahead != 0) ADVANCE(6);
+      END_STATE();
+    case 1:
+      if (lookahead == '"') ADVANCE(8);
+      END_STATE();
+    case 2:
+      ACCEPT_TOKEN(ts_builtin_sym_end);
+      END_STATE();
+    case 3:
+      ACCEPT_TOKEN(anon_sym_LF);
+      END_STATE();
+    case 4:
+      ACCEPT_TOKEN(sym_whitespace);
+      if (set_contains(sym_whitespace_character_set_1, 11, lookahead)) ADVANCE(4);
+      END_STATE();
+    case 5:
+      ACCEPT_TOKEN(sym_null);
+      if ((!eof && set_contains(sym_value_character_set_1, 12, lookahead))) ADVANCE(6);
+      END_STATE();
+    case 6:
+      ACCEPT_TOKEN(sym_value);
+      if ((!eof && set_contains(sym_value_character_set_1, 12, lookahead))) ADVANCE(6);
+      END_STATE();
+    case 7:
+      ACCEPT_TOKEN(sym_string);
+      if (lookahead == '"') ADVANCE(8);
+      if (lookahead == '/') ADVANCE(1);
+      END_STATE();
+    case 8:
+      ACCEPT_TOKEN(sym_unclosed_string);
+      if (lookahead == '"') ADVANCE(7);
+      if (lookahead != 0 &&
+          lookahead != '\n') ADVANCE(8);
+      END_STATE();
+    case 9:
+      ACCEPT_TOKEN(sym_comment);
+      if (lookahead != 0 &&
+          lookahead != '\n') ADVANCE(9);
+      END_STATE();
+    default:
+      return false;
+  }
+}
+
+static const TSLexerMode ts_lex_modes[STATE_COUNT] = {
+  [0] = {.lex_state = 0},
+  [1] = {.lex_state = 0},
+  [2] = {.lex_state = 0},
+  [3] = {.lex_state = 0},
+  [4] = {.lex_state = 0},
+  [5] = {.lex_state = 0},
+  [6] = {.lex_state = 0},
+  [7] = {.lex_state = 0},
+  [8] = {.lex_state = 0},
+  [9] = {.lex_state = 0},
+  [10] = {.lex_state = 0},
+};
+
+static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
+  [STATE(0)] = {
+    [ts_builtin_sym_end] = ACTIONS(1),
+    [anon_sym_LF] = ACTIONS(1),
+    [sym_whitespace] = ACTIONS(1),
+    [sym_null] = ACTIONS(1),
+    [sym_value] = ACTIONS(1),
+    [sym_string] = ACTIONS(1),
+    [sym_unclosed_string] = ACTIONS(1),
+    [sym_comment] = ACTIONS(1),
+  },
+  [STATE(1)] = {
+    [sym_document] = STATE(10),
+    [sym_row] = STATE(5),
+    [aux_sym_document_repeat1] = STATE(6),
+    [aux_sym_row_repeat1] = STATE(3),
+    [ts_builtin_sym_end] = ACTIONS(3),
+    [anon_sym_LF] = ACTIONS(5),
+    [sym_whitespace] = ACTIONS(7),
+    [sym_null] = ACTIONS(9),
+    [sym_value] = ACTIONS(9),
+    [sym_string] = ACTIONS(7),
+    [sym_unclosed_string] = ACTIONS(7),
+    [sym_comment] = ACTIONS(7),
+  },
+  [STATE(2)] = {
+    [sym_row] = STATE(9),
+    [aux_sym_row_repeat1] = STATE(3),
+    [ts_builtin_sym_end] = ACTIONS(11),
+    [anon_sym_LF] = ACTIONS(11),
+    [sym_whitespace] = ACTIONS(7),
+    [sym_null] = ACTIONS(9),
+    [sym_value] = ACTIONS(9),
+    [sym_string] = ACTIONS(7),
+    [sym_unclosed_string] = ACTIONS(7),
+    [sym_comment] = ACTIONS(7),
+  },
+  [STATE(3)] = {
+    [aux_sym_row_repeat1] = STATE(4),
+    [ts_builtin_sym_end] = ACTIONS(13),
+    [anon_sym_LF] = ACTIONS(13),
+    [sym_whitespace] = ACTIONS(15),
+    [sym_null] = ACTIONS(17),
+    [sym_value] = ACTIONS(17),
+    [sym_string] = ACTIONS(15),
+    [sym_unclosed_string] = ACTIONS(15),
+    [sym_comment] = ACTIONS(15),
+  },
+  [STATE(4)] = {
+    [aux_sym_row_repeat1] = STATE(4),
+    [ts_builtin_sym_end] = ACTIONS(19),
+    [anon_sym_LF] = ACTIONS(19),
+    [sym_whitespace] = ACTIONS(21),
+    [sym_null] = ACTIONS(24),
+    [sym_value] = ACTIONS(24),
+    [sym_string] = ACTIONS(21),
+    [sym_unclosed_string] = ACTIONS(21),
+    [sym_comment] = ACTIONS(21),
+  },
+};
+
+static const uint16_t ts_small_parse_table[] = {
+  [0] = 3,
+    ACTIONS(5), 1,
+      anon_sym_LF,
+    ACTIONS(27), 1,
+      ts_builtin_sym_end,
+    STATE(7), 1,
+      aux_sym_document_repeat1,
+  [10] = 3,
+    ACTIONS(5), 1,
+      anon_sym_LF,
+    ACTIONS(27), 1,
+      ts_builtin_sym_end,
+    STATE(8), 1,
+      aux_sym_document_repeat1,
+  [20] = 3,
+    ACTIONS(5), 1,
+      anon_sym_LF,
+    ACTIONS(29), 1,
+      ts_builtin_sym_end,
+    STATE(8), 1,
+      aux_sym_document_repeat1,
+  [30] = 3,
+    ACTIONS(31), 1,
+      ts_builtin_sym_end,
+    ACTIONS(33), 1,
+      anon_sym_LF,
+    STATE(8), 1,
+      aux_sym_document_repeat1,
+  [40] = 1,
+    ACTIONS(31), 2,
+      ts_builtin_sym_end,
+      anon_sym_LF,
+  [45] = 1,
+    ACTIONS(36), 1,
+      ts_builtin_sym_end,
+};
+
+static const uint32_t ts_small_parse_table_map[] = {
+  [SMALL_STATE(5)] = 0,
+  [SMALL_STATE(6)] = 10,
+  [SMALL_STATE(7)] = 20,
+  [SMALL_STATE(8)] = 30,
+  [SMALL_STATE(9)] = 40,
+  [SMALL_STATE(10)] = 45,
+};
+
+static const TSParseActionEntry ts_parse_actions[] = {
+  [0] = {.entry = {.count = 0, .reusable = false}},
+  [1] = {.entry = {.count = 1, .reusable = false}}, RECOVER(),
+  [3] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_document, 0, 0, 0),
+  [5] = {.entry = {.count = 1, .reusable = true}}, SHIFT(2),
+  [7] = {.entry = {.count = 1, .reusable = true}}, SHIFT(3),
+  [9] = {.entry = {.count = 1, .reusable = false}}, SHIFT(3),
+  [11] = {.entry = {.count = 1, .reusable = true}}, REDUCE(aux_sym_document_repeat1, 1, 0, 0),
+  [13] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_row, 1, 0, 0),
+  [15] = {.entry = {.count = 1, .reusable = true}}, SHIFT(4),
+  [17] = {.entry = {.count = 1, .reusable = false}}, SHIFT(4),
+  [19] = {.entry = {.count = 1, .reusable = true}}, REDUCE(aux_sym_row_repeat1, 2, 0, 0),
+  [21] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_row_repeat1, 2, 0, 0), SHIFT_REPEAT(4),
+  [24] = {.entry = {.count = 2, .reusable = false}}, REDUCE(aux_sym_row_repeat1, 2, 0, 0), SHIFT_REPEAT(4),
+  [27] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_document, 1, 0, 0),
+  [29] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_document, 2, 0, 0),
+  [31] = {.entry = {.count = 1, .reusable = true}}, REDUCE(aux_sym_document_repeat1, 2, 0, 0),
+  [33] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_document_repeat1, 2, 0, 0), SHIFT_REPEAT(2),
+  [36] = {.entry = {.count = 1, .reusable = true}},  ACCEPT_INPUT(),
+};
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+#ifdef TREE_SITTER_HIDE_SYMBOLS
+#define TS_PUBLIC
+#elif defined(_WIN32)
+#define TS_PUBLIC __declspec(dllexport)
+#else
+#define TS_PUBLIC __attribute__((visibility("default")))
+#endif
+
+TS_PUBLIC const TSLanguage *tree_sitter_wsv(void) {
+  static const TSLanguage language = {
+    .abi_version = LANGUAGE_VERSION,
+    .symbol_count = SYMBOL_COUNT,
+    .alias_count = ALIAS_COUNT,
+    .token_count = TOKEN_COUNT,
+    .external_token_count = EXTERNAL_TOKEN_COUNT,
+    .state_count = STATE_COUNT,
+    .large_state_count = LARGE_STATE_COUNT,
+    .production_id_count = PRODUCTION_ID_COUNT,
+    .supertype_count = SUPERTYPE_COUNT,
+    .field_count = FIELD_COUNT,
+    .max_alias_sequence_length = MAX_ALIAS_SEQUENCE_LENGTH,
+    .parse_table = &ts_parse_table[0][0],
+    .small_parse_table = ts_small_parse_table,
+    .small_parse_table_map = ts_small_parse_table_map,
+    .parse_actions = ts_parse_actions,
+    .symbol_names = ts_symbol_names,
+    .symbol_metadata = ts_symbol_metadata,
+    .public_symbol_map = ts_symbol_map,
+    .alias_map = ts_non_terminal_alias_map,
+    .alias_sequences = &ts_alias_sequences[0][0],
+    .lex_modes = (const void*)ts_lex_modes,
+    .lex_fn = ts_lex,
+    .primary_state_ids = ts_primary_state_ids,
+    .name = "wsv",
+    .max_reserved_word_set_size = 0,
+    .metadata = {
+      .major_version = 0,
+      .minor_version = 1,
+      .patch_version = 0,
+    },
+  };
+  return &language;
+}
+#ifdef __cplusplus
+}
+#endif